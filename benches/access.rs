@@ -0,0 +1,91 @@
+use std::hint::black_box;
+use std::sync::Arc;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use global_mockable::GlobalMockable;
+use tokio::runtime::{Builder, Runtime};
+
+const READERS: usize = 8;
+const READS_PER_TASK: usize = 1_000;
+const SETS: usize = 10;
+
+struct Value(usize);
+
+static GLOBAL: GlobalMockable<Value> = GlobalMockable::const_new();
+
+async fn get() -> Arc<Value> {
+    GLOBAL.get_or_init(|| async { Arc::new(Value(0)) }).await
+}
+
+fn runtimes() -> [(&'static str, Runtime); 2] {
+    [
+        (
+            "current_thread",
+            Builder::new_current_thread().enable_all().build().unwrap(),
+        ),
+        (
+            "multi_thread",
+            Builder::new_multi_thread().enable_all().build().unwrap(),
+        ),
+    ]
+}
+
+async fn map() -> usize {
+    GLOBAL
+        .with_or_init(|| async { Arc::new(Value(0)) }, |value| black_box(value).0)
+        .await
+}
+
+// A warm `get`, which clones an `Arc` out of the lock-free cache, against
+// `with_or_init`, which only borrows it.
+fn warm_get(c: &mut Criterion) {
+    let mut group = c.benchmark_group("get");
+    for (flavor, runtime) in runtimes() {
+        group.bench_function(BenchmarkId::new("clone", flavor), |b| {
+            b.to_async(&runtime)
+                .iter(|| async { black_box(get().await) });
+        });
+        group.bench_function(BenchmarkId::new("borrow", flavor), |b| {
+            b.to_async(&runtime)
+                .iter(|| async { black_box(map().await) });
+        });
+    }
+    group.finish();
+}
+
+// Concurrent readers while a writer occasionally replaces the value.
+fn readers_with_writer(c: &mut Criterion) {
+    let mut group = c.benchmark_group("readers_with_writer");
+    for (flavor, runtime) in runtimes() {
+        group.bench_with_input(
+            BenchmarkId::new(flavor, READERS),
+            &READERS,
+            |b, &readers| {
+                b.to_async(&runtime).iter(|| async move {
+                    let mut handles: Vec<_> = (0..readers)
+                        .map(|_| {
+                            tokio::spawn(async {
+                                for _ in 0..READS_PER_TASK {
+                                    black_box(get().await);
+                                }
+                            })
+                        })
+                        .collect();
+                    handles.push(tokio::spawn(async {
+                        for _ in 0..SETS {
+                            GLOBAL.set(Arc::new(Value(0))).await;
+                            tokio::task::yield_now().await;
+                        }
+                    }));
+                    for handle in handles {
+                        handle.await.unwrap();
+                    }
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, warm_get, readers_with_writer);
+criterion_main!(benches);
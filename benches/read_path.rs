@@ -0,0 +1,74 @@
+use std::hint::black_box;
+use std::sync::Arc;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use global_mockable::GlobalMockable;
+use tokio::runtime::Runtime;
+use tokio::sync::{OnceCell, RwLock};
+
+const READS_PER_TASK: usize = 1_000;
+
+struct Value;
+
+// The pre-cache design: every read goes through the `RwLock`.
+static LOCKED: RwLock<OnceCell<Arc<Value>>> = RwLock::const_new(OnceCell::const_new());
+static CACHED: GlobalMockable<Value> = GlobalMockable::const_new();
+
+async fn read_locked() -> Arc<Value> {
+    LOCKED
+        .read()
+        .await
+        .get_or_init(|| async { Arc::new(Value) })
+        .await
+        .clone()
+}
+
+async fn read_cached() -> Arc<Value> {
+    CACHED.get_or_init(|| async { Arc::new(Value) }).await
+}
+
+fn concurrent_readers(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("concurrent_readers");
+
+    for tasks in [1, 4, 16] {
+        group.bench_with_input(BenchmarkId::new("rwlock", tasks), &tasks, |b, &tasks| {
+            b.to_async(&runtime).iter(|| async move {
+                let handles: Vec<_> = (0..tasks)
+                    .map(|_| {
+                        tokio::spawn(async {
+                            for _ in 0..READS_PER_TASK {
+                                black_box(read_locked().await);
+                            }
+                        })
+                    })
+                    .collect();
+                for handle in handles {
+                    handle.await.unwrap();
+                }
+            });
+        });
+
+        group.bench_with_input(BenchmarkId::new("cached", tasks), &tasks, |b, &tasks| {
+            b.to_async(&runtime).iter(|| async move {
+                let handles: Vec<_> = (0..tasks)
+                    .map(|_| {
+                        tokio::spawn(async {
+                            for _ in 0..READS_PER_TASK {
+                                black_box(read_cached().await);
+                            }
+                        })
+                    })
+                    .collect();
+                for handle in handles {
+                    handle.await.unwrap();
+                }
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, concurrent_readers);
+criterion_main!(benches);
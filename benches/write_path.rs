@@ -0,0 +1,111 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::future::Future;
+use std::hint::black_box;
+use std::sync::Arc;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use global_mockable::GlobalMockable;
+use tokio::runtime::Runtime;
+use tokio::sync::{OnceCell, RwLock};
+
+// Counts allocations, so the bench can check that writes make none. The
+// count is per thread, leaving out the runtime's worker threads.
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.set(ALLOCATIONS.get() + 1);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+trait Service: Send + Sync {}
+
+struct Value;
+
+impl Service for Value {}
+
+// The pre-cache design: every write rebuilds the `OnceCell` behind the lock.
+static LOCKED: RwLock<OnceCell<Arc<Value>>> = RwLock::const_new(OnceCell::const_new());
+static SWAPPED: GlobalMockable<Value> = GlobalMockable::const_new();
+static SWAPPED_DYN: GlobalMockable<dyn Service> = GlobalMockable::const_new();
+
+async fn replace_locked(value: Arc<Value>) -> Option<Arc<Value>> {
+    let mut cell = LOCKED.write().await;
+    std::mem::replace(&mut *cell, OnceCell::new_with(Some(value))).into_inner()
+}
+
+// The incoming `Arc`s are allocated before counting starts, so only the
+// writes' own allocations are counted.
+fn assert_no_allocations<V, F, Fut>(runtime: &Runtime, name: &str, value: fn() -> Arc<V>, write: F)
+where
+    V: ?Sized,
+    F: Fn(Arc<V>) -> Fut,
+    Fut: Future,
+{
+    const WRITES: usize = 1000;
+    runtime.block_on(async {
+        // The first writes allocate the storage that later ones reuse.
+        for _ in 0..2 {
+            write(value()).await;
+        }
+        let values: Vec<_> = (0..WRITES).map(|_| value()).collect();
+        let before = ALLOCATIONS.get();
+        // Unconstrained, so running out of coop budget cannot make a write
+        // wait, and have the lock watchdog record it.
+        for value in values {
+            black_box(tokio::task::unconstrained(write(value)).await);
+        }
+        let allocations = ALLOCATIONS.get() - before;
+        assert_eq!(allocations, 0, "{name} allocated during {WRITES} writes");
+    });
+}
+
+// Both sides allocate the incoming `Arc` per iteration, so the difference is
+// what the write itself costs.
+fn replace(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    assert_no_allocations(
+        &runtime,
+        "replace_arc",
+        || Arc::new(Value),
+        |value| SWAPPED.replace_arc(value),
+    );
+    assert_no_allocations(
+        &runtime,
+        "replace_arc_dyn",
+        || Arc::new(Value) as Arc<dyn Service>,
+        |value| SWAPPED_DYN.replace_arc(value),
+    );
+
+    let mut group = c.benchmark_group("replace");
+    group.bench_function("rebuild_once_cell", |b| {
+        b.to_async(&runtime)
+            .iter(|| async { black_box(replace_locked(Arc::new(Value)).await) });
+    });
+    group.bench_function("replace_arc", |b| {
+        b.to_async(&runtime)
+            .iter(|| async { black_box(SWAPPED.replace_arc(Arc::new(Value)).await) });
+    });
+    group.bench_function("replace_arc_dyn", |b| {
+        b.to_async(&runtime)
+            .iter(|| async { black_box(SWAPPED_DYN.replace_arc(Arc::new(Value)).await) });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, replace);
+criterion_main!(benches);
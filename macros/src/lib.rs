@@ -0,0 +1,123 @@
+//! Attribute macros for `global-mockable`, re-exported from that crate.
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Ident, ItemFn, ItemTrait, Path, TraitItem, Visibility, parse_macro_input};
+
+/// Turns a trait into a mockable one by generating its accessor, as
+/// `define_global_mockable!` would.
+///
+/// The accessor is named after the trait with a `Global` suffix, or
+/// `name = ...`. Its default is either `default = path::to::init` or an
+/// associated function in the trait marked `#[default_impl]`, which is moved
+/// out of the trait so the trait stays object safe:
+///
+/// ```ignore
+/// #[global_mockable::mockable]
+/// pub trait Greeter: Send + Sync {
+///     fn greet(&self) -> &'static str;
+///
+///     #[default_impl]
+///     async fn default_greeter() -> Arc<dyn Greeter> {
+///         Arc::new(DefaultGreeter)
+///     }
+/// }
+///
+/// GreeterGlobal::get().await.greet();
+/// ```
+#[proc_macro_attribute]
+pub fn mockable(args: TokenStream, item: TokenStream) -> TokenStream {
+    let mut name: Option<Ident> = None;
+    let mut default: Option<Path> = None;
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("name") {
+            name = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("default") {
+            default = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("expected `name` or `default`"))
+        }
+    });
+    parse_macro_input!(args with parser);
+    let mut item = parse_macro_input!(item as ItemTrait);
+
+    expand(name, default, &mut item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(
+    name: Option<Ident>,
+    default: Option<Path>,
+    item: &mut ItemTrait,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let default_fn = take_default_impl(item)?;
+    let default_path = match (default, &default_fn) {
+        (Some(_), Some(function)) => {
+            return Err(syn::Error::new_spanned(
+                &function.sig,
+                "`#[default_impl]` conflicts with the `default` argument",
+            ));
+        }
+        (Some(path), None) => path,
+        (None, Some(function)) => function.sig.ident.clone().into(),
+        (None, None) => {
+            return Err(syn::Error::new_spanned(
+                &item.ident,
+                "a mockable trait needs `default = ...` or a `#[default_impl]` function",
+            ));
+        }
+    };
+
+    let name = name.unwrap_or_else(|| format_ident!("{}Global", item.ident));
+    let trait_name = &item.ident;
+    Ok(quote! {
+        #item
+        #default_fn
+        ::global_mockable::define_global_mockable!(#name, dyn #trait_name, #default_path);
+    })
+}
+
+// Removes the trait's `#[default_impl]` function, if any, as a free function.
+fn take_default_impl(item: &mut ItemTrait) -> syn::Result<Option<ItemFn>> {
+    let mut found = None;
+    let mut error = None;
+    item.items.retain_mut(|trait_item| {
+        let TraitItem::Fn(function) = trait_item else {
+            return true;
+        };
+        let Some(index) = function
+            .attrs
+            .iter()
+            .position(|attr| attr.path().is_ident("default_impl"))
+        else {
+            return true;
+        };
+        function.attrs.remove(index);
+
+        let result = match (&found, &function.default) {
+            (Some(_), _) => Err("only one function can be `#[default_impl]`"),
+            (None, None) => Err("a `#[default_impl]` function needs a body"),
+            (None, Some(block)) => {
+                found = Some(ItemFn {
+                    attrs: function.attrs.clone(),
+                    vis: Visibility::Inherited,
+                    sig: function.sig.clone(),
+                    block: Box::new(block.clone()),
+                });
+                Ok(())
+            }
+        };
+        if let Err(message) = result {
+            error.get_or_insert_with(|| syn::Error::new_spanned(&function.sig, message));
+        }
+        false
+    });
+
+    match error {
+        Some(error) => Err(error),
+        None => Ok(found),
+    }
+}
@@ -0,0 +1,100 @@
+use std::any::{Any, TypeId};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use crate::{Cell, GlobalMockable};
+
+type AnyFuture = Pin<Box<dyn Future<Output = Arc<dyn Any + Send + Sync>> + Send>>;
+
+/// A type-erased handle to a static [`GlobalMockable`] and its default, so
+/// mockables of different types can be stored side by side, e.g. in a map
+/// keyed by [`type_id`](Self::type_id).
+///
+/// Only sized value types can be erased; store a trait object behind a
+/// concrete wrapper type to use it here.
+pub struct AnyGlobalMockable {
+    type_id: TypeId,
+    type_name: &'static str,
+    get: Box<dyn Fn() -> AnyFuture + Send + Sync>,
+}
+
+impl AnyGlobalMockable {
+    pub fn new<T, C, F, Fut>(mockable: &'static GlobalMockable<T, C>, default: F) -> Self
+    where
+        T: Any + Send + Sync,
+        C: Cell<Arc<T>> + 'static,
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Arc<T>> + Send + 'static,
+    {
+        let default = Arc::new(default);
+        AnyGlobalMockable {
+            type_id: TypeId::of::<T>(),
+            type_name: std::any::type_name::<T>(),
+            get: Box::new(move || {
+                let default = Arc::clone(&default);
+                Box::pin(async move {
+                    let value: Arc<dyn Any + Send + Sync> =
+                        mockable.get_or_init(|| default()).await;
+                    value
+                })
+            }),
+        }
+    }
+
+    /// The [`TypeId`] of the erased value type.
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub async fn get(&self) -> Arc<dyn Any + Send + Sync> {
+        (self.get)().await
+    }
+
+    /// Returns the current value if it is a `T`.
+    pub async fn get_as<T>(&self) -> Option<Arc<T>>
+    where
+        T: Any + Send + Sync,
+    {
+        self.get().await.downcast().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[derive(Debug, PartialEq)]
+    struct Port(u16);
+
+    #[derive(Debug, PartialEq)]
+    struct Host(&'static str);
+
+    static PORT: GlobalMockable<Port> = GlobalMockable::const_new();
+    static HOST: GlobalMockable<Host> = GlobalMockable::const_new();
+
+    #[tokio::test]
+    async fn downcasts_heterogeneous_mockables() {
+        let registry: HashMap<TypeId, AnyGlobalMockable> = [
+            AnyGlobalMockable::new(&PORT, || async { Arc::new(Port(8080)) }),
+            AnyGlobalMockable::new(&HOST, || async { Arc::new(Host("localhost")) }),
+        ]
+        .into_iter()
+        .map(|mockable| (mockable.type_id(), mockable))
+        .collect();
+
+        let port = &registry[&TypeId::of::<Port>()];
+        assert_eq!(port.get_as::<Port>().await.unwrap().0, 8080);
+        assert!(port.get_as::<Host>().await.is_none());
+
+        HOST.set(Arc::new(Host("mock"))).await;
+        let host = &registry[&TypeId::of::<Host>()];
+        assert_eq!(host.get_as::<Host>().await.unwrap().0, "mock");
+        assert!(host.type_name().ends_with("Host"));
+    }
+}
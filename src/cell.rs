@@ -0,0 +1,168 @@
+use std::sync::Arc;
+
+use crate::sync::ArcSwapOption;
+
+/// Storage for the current value of a [`GlobalMockable`](crate::GlobalMockable).
+///
+/// The mockable layers locking, single-flight initialization, freezing and
+/// change notification on top, so a cell only has to hold a value. Writes are
+/// serialized by the mockable, but [`get`](Self::get) runs concurrently with
+/// them and must return either the value before or after the write.
+pub trait Cell<V>: Send + Sync {
+    fn get(&self) -> Option<V>;
+
+    fn set(&self, value: V);
+
+    fn clear(&self);
+
+    /// Runs `f` on the current value. Cells that can lend out their value
+    /// override this to skip the clone [`get`](Self::get) makes.
+    fn with<R>(&self, f: impl FnOnce(Option<&V>) -> R) -> R {
+        f(self.get().as_ref())
+    }
+
+    /// Removes and returns the current value.
+    fn take(&self) -> Option<V> {
+        let value = self.get();
+        self.clear();
+        value
+    }
+}
+
+/// The default [`Cell`], which never blocks readers.
+pub struct ArcSwapCell<V> {
+    // `ArcSwap` needs a sized pointee, hence the extra `Arc` around `V`.
+    value: ArcSwapOption<Option<V>>,
+    // The previous write's `Arc`, emptied once no reader held it, so a
+    // steady stream of writes does not allocate.
+    spare: std::sync::Mutex<Option<Arc<Option<V>>>>,
+}
+
+impl<V> ArcSwapCell<V> {
+    pub const fn new() -> Self {
+        ArcSwapCell {
+            value: ArcSwapOption::const_empty(),
+            spare: std::sync::Mutex::new(None),
+        }
+    }
+}
+
+impl<V: Clone> ArcSwapCell<V> {
+    fn replace(&self, value: Option<V>) -> Option<V> {
+        let mut spare = self.spare.lock().unwrap_or_else(|err| err.into_inner());
+        let slot = value.map(|value| match spare.take() {
+            Some(mut slot) => {
+                *Arc::get_mut(&mut slot).expect("the spare slot is never shared") = Some(value);
+                slot
+            }
+            None => Arc::new(Some(value)),
+        });
+
+        let mut previous = self.value.swap(slot)?;
+        match Arc::get_mut(&mut previous) {
+            Some(value) => {
+                let value = value.take();
+                *spare = Some(previous);
+                value
+            }
+            // A reader still holds it, so it cannot be reused.
+            None => Option::clone(&previous),
+        }
+    }
+}
+
+impl<V> Default for ArcSwapCell<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> Cell<V> for ArcSwapCell<V>
+where
+    V: Clone + Send + Sync,
+{
+    fn get(&self) -> Option<V> {
+        self.with(|value| value.cloned())
+    }
+
+    fn set(&self, value: V) {
+        self.replace(Some(value));
+    }
+
+    fn clear(&self) {
+        self.replace(None);
+    }
+
+    fn with<R>(&self, f: impl FnOnce(Option<&V>) -> R) -> R {
+        f(self.value.load().as_deref().and_then(Option::as_ref))
+    }
+
+    fn take(&self) -> Option<V> {
+        self.replace(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GlobalMockable;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct MutexCell<V> {
+        value: Mutex<Option<V>>,
+        writes: AtomicUsize,
+    }
+
+    impl<V: Clone + Send> Cell<V> for MutexCell<V> {
+        fn get(&self) -> Option<V> {
+            self.value.lock().unwrap().clone()
+        }
+
+        fn set(&self, value: V) {
+            self.writes.fetch_add(1, Ordering::SeqCst);
+            *self.value.lock().unwrap() = Some(value);
+        }
+
+        fn clear(&self) {
+            self.writes.fetch_add(1, Ordering::SeqCst);
+            *self.value.lock().unwrap() = None;
+        }
+    }
+
+    #[tokio::test]
+    async fn custom_cell_backs_the_mockable() {
+        let mockable = GlobalMockable::<u32, MutexCell<Arc<u32>>>::default();
+
+        assert_eq!(*mockable.get_or_init(|| async { Arc::new(1) }).await, 1);
+        mockable.set(Arc::new(2)).await;
+        assert_eq!(*mockable.get_or_init(|| async { Arc::new(1) }).await, 2);
+
+        let fork = mockable.fork().await;
+        mockable.clear().await;
+        assert_eq!(*mockable.get_or_init(|| async { Arc::new(3) }).await, 3);
+        assert_eq!(fork.clone_inner().await, Some(2));
+        assert_eq!(mockable.cell.writes.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn writes_reuse_the_previous_slot() {
+        let cell = ArcSwapCell::<Arc<u32>>::new();
+        let slot = |cell: &ArcSwapCell<Arc<u32>>| cell.value.load().as_ref().map(Arc::as_ptr);
+
+        cell.set(Arc::new(1));
+        cell.set(Arc::new(2));
+        let second = slot(&cell);
+        cell.set(Arc::new(3));
+        cell.set(Arc::new(4));
+        assert_eq!(slot(&cell), second);
+
+        // A slot a reader still holds is left to it.
+        let held = cell.value.load();
+        cell.set(Arc::new(5));
+        assert_eq!(cell.take().as_deref(), Some(&5));
+        assert_eq!(held.as_deref(), Some(&Some(Arc::new(4))));
+        assert!(cell.get().is_none());
+    }
+}
@@ -0,0 +1,78 @@
+/// Why an operation on a [`GlobalMockable`](crate::GlobalMockable) failed.
+///
+/// The panicking methods panic with these; their `try_` counterparts return
+/// them instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum GlobalMockableError {
+    /// The instance is frozen, so its value cannot change, and if it was
+    /// frozen while empty it can never be initialized.
+    #[error("instance is frozen")]
+    Frozen,
+    /// The initializer (transitively) depends on the value it is producing.
+    #[error("cyclic initialization detected")]
+    Cycle,
+    /// The instance is empty and the operation does not run a default.
+    #[error("instance was never initialized")]
+    Uninitialized,
+    /// A blocking operation was called outside a multi-thread Tokio runtime.
+    #[error("no multi-thread Tokio runtime is running")]
+    NoRuntime,
+    /// A [`FactoryBackedMockable`](crate::FactoryBackedMockable) is empty and
+    /// its registry has no factory under its key.
+    #[error("no factory is registered under the key")]
+    UnknownFactory,
+}
+
+/// Why [`try_get_or_try_init`](crate::GlobalMockable::try_get_or_try_init)
+/// produced no value: either the initializer failed, or the instance could
+/// not run it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum TryInitError<E> {
+    /// The initializer returned this error.
+    #[error("initializer failed: {0}")]
+    Init(E),
+    #[error(transparent)]
+    Mockable(#[from] GlobalMockableError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let cases = [
+            (GlobalMockableError::Frozen, "instance is frozen"),
+            (GlobalMockableError::Cycle, "cyclic initialization detected"),
+            (
+                GlobalMockableError::Uninitialized,
+                "instance was never initialized",
+            ),
+            (
+                GlobalMockableError::NoRuntime,
+                "no multi-thread Tokio runtime is running",
+            ),
+            (
+                GlobalMockableError::UnknownFactory,
+                "no factory is registered under the key",
+            ),
+        ];
+        for (err, message) in cases {
+            assert_eq!(err.to_string(), message);
+        }
+    }
+
+    #[test]
+    fn try_init_error_display() {
+        let init = TryInitError::Init("connection refused");
+        assert_eq!(init.to_string(), "initializer failed: connection refused");
+
+        let mockable = TryInitError::<&str>::from(GlobalMockableError::Frozen);
+        assert_eq!(
+            mockable,
+            TryInitError::Mockable(GlobalMockableError::Frozen)
+        );
+        assert_eq!(mockable.to_string(), "instance is frozen");
+    }
+}
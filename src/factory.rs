@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::{BoxedInit, GlobalMockable, GlobalMockableError};
+
+/// Factories by name, shared by the [`FactoryBackedMockable`]s resolving
+/// through them.
+pub type FactoryRegistry<T> = HashMap<String, BoxedInit<T>>;
+
+/// A [`GlobalMockable`] whose default is the factory registered under its key,
+/// for plugin architectures where the implementation is chosen by name.
+///
+/// The factory runs on first access; `set` and `clear` override it as usual.
+pub struct FactoryBackedMockable<T>
+where
+    T: ?Sized + Send + Sync,
+{
+    inner: GlobalMockable<T>,
+    registry: Arc<FactoryRegistry<T>>,
+    key: String,
+}
+
+impl<T> FactoryBackedMockable<T>
+where
+    T: ?Sized + Send + Sync,
+{
+    pub fn new(registry: Arc<FactoryRegistry<T>>, key: impl Into<String>) -> Self {
+        FactoryBackedMockable {
+            inner: GlobalMockable::const_new(),
+            registry,
+            key: key.into(),
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// # Panics
+    ///
+    /// Panics in the cases where [`try_get`](Self::try_get) returns an error.
+    pub async fn get(&self) -> Arc<T> {
+        self.try_get().await.unwrap_or_else(|err| {
+            panic!(
+                "FactoryBackedMockable<{}> `{}`: {err}",
+                std::any::type_name::<T>(),
+                self.key
+            )
+        })
+    }
+
+    /// Returns the current value, building it with the registered factory if
+    /// empty. Fails with [`GlobalMockableError::UnknownFactory`] if it is
+    /// empty and there is no factory under the key.
+    pub async fn try_get(&self) -> Result<Arc<T>, GlobalMockableError> {
+        let Some(factory) = self.registry.get(&self.key) else {
+            return self
+                .inner
+                .try_current()
+                .await
+                .map_err(|_| GlobalMockableError::UnknownFactory);
+        };
+        self.inner.try_get_or_init(factory).await
+    }
+
+    pub async fn set(&self, value: Arc<T>) {
+        self.inner.set(value).await;
+    }
+
+    pub async fn clear(&self) {
+        self.inner.clear().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    trait Plugin: Send + Sync {
+        fn name(&self) -> &'static str;
+    }
+
+    struct Named(&'static str);
+
+    impl Plugin for Named {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+    }
+
+    fn factory(name: &'static str) -> BoxedInit<dyn Plugin> {
+        Box::new(move || Box::pin(async move { Arc::new(Named(name)) as Arc<dyn Plugin> }))
+    }
+
+    fn registry() -> Arc<FactoryRegistry<dyn Plugin>> {
+        let mut registry = FactoryRegistry::new();
+        registry.insert("csv".to_owned(), factory("csv"));
+        registry.insert("json".to_owned(), factory("json"));
+        Arc::new(registry)
+    }
+
+    #[tokio::test]
+    async fn resolves_factory_by_key() {
+        let registry = registry();
+        let csv = FactoryBackedMockable::new(Arc::clone(&registry), "csv");
+        let json = FactoryBackedMockable::new(registry, "json");
+
+        assert_eq!(csv.get().await.name(), "csv");
+        assert_eq!(json.get().await.name(), "json");
+
+        csv.set(Arc::new(Named("mock"))).await;
+        assert_eq!(csv.get().await.name(), "mock");
+        csv.clear().await;
+        assert_eq!(csv.get().await.name(), "csv");
+    }
+
+    #[tokio::test]
+    async fn unknown_key_fails_until_set() {
+        let yaml = FactoryBackedMockable::new(registry(), "yaml");
+        assert_eq!(
+            yaml.try_get().await.err(),
+            Some(GlobalMockableError::UnknownFactory)
+        );
+
+        yaml.set(Arc::new(Named("mock"))).await;
+        assert_eq!(yaml.get().await.name(), "mock");
+    }
+}
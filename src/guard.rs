@@ -0,0 +1,154 @@
+use std::sync::Arc;
+
+use tokio::runtime::Handle;
+
+use crate::{ArcSwapCell, Cell, GlobalMockable, Origin};
+
+/// Restores the value a [`GlobalMockable`] held before
+/// [`swap`](GlobalMockable::swap).
+///
+/// Call [`restore`](Self::restore) to choose exactly when that happens. As a
+/// fallback, dropping the guard restores the value as soon as the lock allows
+/// and prints a warning, through `log` if that feature is enabled. On a
+/// current-thread runtime where another task holds the lock, that task makes
+/// the restore when it lets go, unless it changed the value itself.
+#[must_use = "dropping a `SwapGuard` restores the previous value immediately; bind it to a variable and call `restore`"]
+pub struct SwapGuard<'a, T, C = ArcSwapCell<Arc<T>>>
+where
+    T: ?Sized + Send + Sync,
+    C: Cell<Arc<T>>,
+{
+    mockable: &'a GlobalMockable<T, C>,
+    previous: Option<Option<Arc<T>>>,
+    origin: Origin,
+}
+
+impl<'a, T, C> SwapGuard<'a, T, C>
+where
+    T: ?Sized + Send + Sync,
+    C: Cell<Arc<T>>,
+{
+    pub(crate) fn new(
+        mockable: &'a GlobalMockable<T, C>,
+        previous: Option<Arc<T>>,
+        origin: Origin,
+    ) -> Self {
+        SwapGuard {
+            mockable,
+            previous: Some(previous),
+            origin,
+        }
+    }
+
+    /// Reinstates the previous value with its [`Origin`], or empties the
+    /// instance if there was none.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance has been [frozen](GlobalMockable::freeze).
+    pub async fn restore(mut self) {
+        let previous = self.previous.take().flatten();
+        self.mockable.restore(previous, self.origin).await;
+    }
+}
+
+impl<T, C> Drop for SwapGuard<'_, T, C>
+where
+    T: ?Sized + Send + Sync,
+    C: Cell<Arc<T>>,
+{
+    fn drop(&mut self) {
+        let Some(previous) = self.previous.take() else {
+            return;
+        };
+
+        warn(format_args!(
+            "SwapGuard for GlobalMockable<{}> dropped without `restore().await`",
+            std::any::type_name::<T>()
+        ));
+        if !self.mockable.store_blocking(previous, self.origin) {
+            warn_frozen::<T>();
+        }
+    }
+}
+
+/// Restores the value a [`GlobalMockable`] held before
+/// [`set_scoped`](GlobalMockable::set_scoped) when dropped.
+#[must_use = "dropping a `RestoreOnDrop` restores the previous value immediately"]
+pub struct RestoreOnDrop<T, C = ArcSwapCell<Arc<T>>>
+where
+    T: ?Sized + Send + Sync + 'static,
+    C: Cell<Arc<T>> + 'static,
+{
+    mockable: &'static GlobalMockable<T, C>,
+    previous: Option<Option<Arc<T>>>,
+    origin: Origin,
+    handle: Option<Handle>,
+}
+
+impl<T, C> RestoreOnDrop<T, C>
+where
+    T: ?Sized + Send + Sync + 'static,
+    C: Cell<Arc<T>> + 'static,
+{
+    pub(crate) fn new(
+        mockable: &'static GlobalMockable<T, C>,
+        previous: Option<Arc<T>>,
+        origin: Origin,
+    ) -> Self {
+        RestoreOnDrop {
+            mockable,
+            previous: Some(previous),
+            origin,
+            handle: Handle::try_current().ok(),
+        }
+    }
+}
+
+impl<T, C> Drop for RestoreOnDrop<T, C>
+where
+    T: ?Sized + Send + Sync + 'static,
+    C: Cell<Arc<T>> + 'static,
+{
+    fn drop(&mut self) {
+        let Some(previous) = self.previous.take() else {
+            return;
+        };
+
+        let origin = self.origin;
+        match self.mockable.try_store_now(&previous, origin) {
+            Some(true) => {}
+            Some(false) => warn_frozen::<T>(),
+            None => match &self.handle {
+                Some(handle) => {
+                    let mockable = self.mockable;
+                    let deferred = mockable.defer_restore(previous, origin);
+                    handle.spawn(async move {
+                        let write = mockable.write().await;
+                        mockable.store_deferred(&write, deferred);
+                    });
+                }
+                None => warn(format_args!(
+                    "could not restore GlobalMockable<{}> without a Tokio runtime",
+                    std::any::type_name::<T>()
+                )),
+            },
+        }
+    }
+}
+
+pub(crate) fn warn_frozen<T: ?Sized>() {
+    warn(format_args!(
+        "could not restore GlobalMockable<{}>, which was frozen meanwhile",
+        std::any::type_name::<T>()
+    ));
+}
+
+// Reported through `log` when that feature is enabled, and on stderr
+// otherwise, so a leaked mock never goes unnoticed.
+pub(crate) fn warn(message: std::fmt::Arguments<'_>) {
+    #[cfg(feature = "log")]
+    log::warn!("{message}");
+    #[cfg(not(feature = "log"))]
+    eprintln!("warning: {message}");
+}
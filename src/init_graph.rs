@@ -0,0 +1,139 @@
+//! Deadlock detection for default initialization.
+//!
+//! Every default initializer runs as part of an *init chain*: the task-local
+//! context started by the outermost `get_or_init` and inherited by any nested
+//! `get_or_init` its factory performs. The graph records which chain holds each
+//! global's init lock and which global each chain is waiting on, so a wait that
+//! would close a cycle is refused instead of hanging forever.
+
+use std::future::Future;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+tokio::task_local! {
+    static CHAIN: u64;
+}
+
+static NEXT_CHAIN: AtomicU64 = AtomicU64::new(0);
+static GRAPH: Mutex<Graph> = Mutex::new(Graph {
+    holders: Vec::new(),
+    waiting: Vec::new(),
+});
+
+struct Graph {
+    // (global, chain holding its init lock)
+    holders: Vec<(usize, u64)>,
+    // (chain, global it is waiting to initialize)
+    waiting: Vec<(u64, usize)>,
+}
+
+impl Graph {
+    fn holder_of(&self, global: usize) -> Option<u64> {
+        self.holders
+            .iter()
+            .find(|(held, _)| *held == global)
+            .map(|(_, chain)| *chain)
+    }
+
+    fn awaited_by(&self, chain: u64) -> Option<usize> {
+        self.waiting
+            .iter()
+            .find(|(waiter, _)| *waiter == chain)
+            .map(|(_, global)| *global)
+    }
+
+    fn closes_cycle(&self, chain: u64, global: usize) -> bool {
+        let mut next = global;
+        while let Some(holder) = self.holder_of(next) {
+            if holder == chain {
+                return true;
+            }
+            match self.awaited_by(holder) {
+                Some(global) => next = global,
+                None => return false,
+            }
+        }
+        false
+    }
+}
+
+fn graph() -> std::sync::MutexGuard<'static, Graph> {
+    GRAPH.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// The init chain the current task belongs to, if it is running a factory.
+pub(crate) fn current_chain() -> Option<u64> {
+    CHAIN.try_with(|chain| *chain).ok()
+}
+
+pub(crate) fn new_chain() -> u64 {
+    NEXT_CHAIN.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Runs `fut` as part of `chain`, unless the task already belongs to it.
+pub(crate) async fn within_chain<Fut: Future>(chain: u64, fut: Fut) -> Fut::Output {
+    if current_chain() == Some(chain) {
+        fut.await
+    } else {
+        CHAIN.scope(chain, fut).await
+    }
+}
+
+/// Records that `chain` is about to wait for `global`'s init lock.
+///
+/// Returns `None` if waiting would deadlock: `global` is held by `chain`
+/// itself, or by a chain that is (transitively) waiting on `chain`.
+pub(crate) fn wait_for(chain: u64, global: usize) -> Option<Waiting> {
+    let mut graph = graph();
+    if graph.closes_cycle(chain, global) {
+        return None;
+    }
+    graph.waiting.push((chain, global));
+    Some(Waiting { chain, global })
+}
+
+pub(crate) struct Waiting {
+    chain: u64,
+    global: usize,
+}
+
+impl Waiting {
+    /// Converts the wait into ownership once the init lock is acquired.
+    pub(crate) fn acquired(self) -> Holding {
+        let holding = Holding {
+            global: self.global,
+        };
+        graph().holders.push((self.global, self.chain));
+        holding
+    }
+}
+
+impl Drop for Waiting {
+    fn drop(&mut self) {
+        let mut graph = graph();
+        if let Some(index) = graph
+            .waiting
+            .iter()
+            .position(|entry| *entry == (self.chain, self.global))
+        {
+            graph.waiting.swap_remove(index);
+        }
+    }
+}
+
+pub(crate) struct Holding {
+    global: usize,
+}
+
+impl Drop for Holding {
+    fn drop(&mut self) {
+        let mut graph = graph();
+        if let Some(index) = graph
+            .holders
+            .iter()
+            .position(|(global, _)| *global == self.global)
+        {
+            graph.holders.swap_remove(index);
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+
+/// A family of mockable values, one per key, for defaults that depend on
+/// something only known at runtime, such as a tenant.
+///
+/// Without a capacity the map grows with every new key. With one, the least
+/// recently used entry is dropped once it would be exceeded, and is simply
+/// initialized again on its next access.
+///
+/// Unlike [`GlobalMockable`](crate::GlobalMockable), initialization is not
+/// single-flight: tasks racing on an empty key may each run `f`, and the
+/// first value stored wins.
+pub struct KeyedGlobalMockable<K, T: ?Sized> {
+    entries: Mutex<Option<Entries<K, T>>>,
+    capacity: Option<NonZeroUsize>,
+}
+
+struct Entries<K, T: ?Sized> {
+    map: HashMap<K, Entry<T>>,
+    // Bumped on every access, so the smallest `last_used` is the LRU entry.
+    clock: u64,
+}
+
+struct Entry<T: ?Sized> {
+    value: Arc<T>,
+    last_used: u64,
+}
+
+impl<K, T> KeyedGlobalMockable<K, T>
+where
+    K: Eq + Hash + Clone,
+    T: ?Sized + Send + Sync,
+{
+    pub const fn const_new() -> Self {
+        KeyedGlobalMockable {
+            entries: Mutex::new(None),
+            capacity: None,
+        }
+    }
+
+    /// Creates an instance that keeps at most `capacity` entries.
+    pub const fn with_capacity(capacity: NonZeroUsize) -> Self {
+        KeyedGlobalMockable {
+            entries: Mutex::new(None),
+            capacity: Some(capacity),
+        }
+    }
+
+    pub async fn get_or_init<F, Fut>(&self, key: &K, f: F) -> Arc<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Arc<T>>,
+    {
+        if let Some(value) = self.with_entries(|entries| entries.touch(key)) {
+            return value;
+        }
+        let value = f().await;
+
+        self.with_entries(|entries| match entries.touch(key) {
+            Some(existing) => existing,
+            None => {
+                entries.insert(key.clone(), Arc::clone(&value), self.capacity);
+                value
+            }
+        })
+    }
+
+    pub async fn set(&self, key: K, value: Arc<T>) {
+        self.with_entries(|entries| entries.insert(key, value, self.capacity));
+    }
+
+    pub async fn clear(&self, key: &K) {
+        self.with_entries(|entries| entries.map.remove(key));
+    }
+
+    pub async fn clear_all(&self) {
+        self.with_entries(|entries| entries.map.clear());
+    }
+
+    /// The number of keys currently holding a value.
+    pub fn len(&self) -> usize {
+        self.with_entries(|entries| entries.map.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Creates the map on first use, which keeps the constructors `const`.
+    fn with_entries<R>(&self, f: impl FnOnce(&mut Entries<K, T>) -> R) -> R {
+        let mut entries = self.entries.lock().unwrap_or_else(|err| err.into_inner());
+        f(entries.get_or_insert_with(|| Entries {
+            map: HashMap::new(),
+            clock: 0,
+        }))
+    }
+}
+
+impl<K, T> Default for KeyedGlobalMockable<K, T>
+where
+    K: Eq + Hash + Clone,
+    T: ?Sized + Send + Sync,
+{
+    fn default() -> Self {
+        Self::const_new()
+    }
+}
+
+impl<K: Eq + Hash + Clone, T: ?Sized> Entries<K, T> {
+    fn touch(&mut self, key: &K) -> Option<Arc<T>> {
+        self.clock += 1;
+        let entry = self.map.get_mut(key)?;
+        entry.last_used = self.clock;
+        Some(Arc::clone(&entry.value))
+    }
+
+    fn insert(&mut self, key: K, value: Arc<T>, capacity: Option<NonZeroUsize>) {
+        self.clock += 1;
+        let entry = Entry {
+            value,
+            last_used: self.clock,
+        };
+        if self.map.insert(key, entry).is_some() {
+            return;
+        }
+
+        if let Some(capacity) = capacity
+            && self.map.len() > capacity.get()
+        {
+            let oldest = self
+                .map
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.map.remove(&oldest);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn evicts_least_recently_used() {
+        let keyed =
+            KeyedGlobalMockable::<&str, usize>::with_capacity(NonZeroUsize::new(2).unwrap());
+        let inits = AtomicUsize::new(0);
+        let init = || async { Arc::new(inits.fetch_add(1, Ordering::SeqCst)) };
+
+        assert_eq!(*keyed.get_or_init(&"a", init).await, 0);
+        assert_eq!(*keyed.get_or_init(&"b", init).await, 1);
+        // Using "a" again leaves "b" as the least recently used.
+        assert_eq!(*keyed.get_or_init(&"a", init).await, 0);
+        assert_eq!(*keyed.get_or_init(&"c", init).await, 2);
+        assert_eq!(keyed.len(), 2);
+
+        assert_eq!(*keyed.get_or_init(&"a", init).await, 0);
+        assert_eq!(*keyed.get_or_init(&"b", init).await, 3);
+    }
+
+    #[tokio::test]
+    async fn unbounded_by_default() {
+        let keyed = KeyedGlobalMockable::<usize, usize>::const_new();
+        for key in 0..100 {
+            keyed
+                .get_or_init(&key, || async move { Arc::new(key) })
+                .await;
+        }
+        assert_eq!(keyed.len(), 100);
+
+        keyed.set(7, Arc::new(70)).await;
+        assert_eq!(*keyed.get_or_init(&7, || async { Arc::new(0) }).await, 70);
+        keyed.clear(&7).await;
+        assert_eq!(*keyed.get_or_init(&7, || async { Arc::new(0) }).await, 0);
+    }
+}
@@ -0,0 +1,97 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use crate::GlobalMockable;
+
+/// A [`GlobalMockable`] that stores its default initializer, monomorphized
+/// over the initializer's type rather than boxed like
+/// [`BoxedInit`](crate::BoxedInit).
+///
+/// The price is that the type has to be named wherever it is declared, and
+/// neither closures nor the futures of `async fn`s can be named. Locals and
+/// struct fields can rely on inference; a `static` needs an initializer with a
+/// nameable type, such as a function pointer returning a hand-written future
+/// or [`std::future::Ready`]:
+///
+/// ```
+/// use std::future::{Ready, ready};
+/// use std::sync::Arc;
+///
+/// use global_mockable::GlobalMockableLazy;
+///
+/// fn default_port() -> Ready<Arc<u16>> {
+///     ready(Arc::new(8080))
+/// }
+///
+/// static PORT: GlobalMockableLazy<u16, fn() -> Ready<Arc<u16>>> =
+///     GlobalMockableLazy::const_new(default_port);
+/// ```
+pub struct GlobalMockableLazy<T, F>
+where
+    T: ?Sized + Send + Sync,
+{
+    inner: GlobalMockable<T>,
+    init: F,
+}
+
+impl<T, F, Fut> GlobalMockableLazy<T, F>
+where
+    T: ?Sized + Send + Sync,
+    F: Fn() -> Fut,
+    Fut: Future<Output = Arc<T>> + Send,
+{
+    pub const fn const_new(init: F) -> Self {
+        GlobalMockableLazy {
+            inner: GlobalMockable::const_new(),
+            init,
+        }
+    }
+
+    pub async fn get(&self) -> Arc<T> {
+        self.inner.get_or_init(&self.init).await
+    }
+
+    pub async fn set(&self, value: Arc<T>) {
+        self.inner.set(value).await;
+    }
+
+    pub async fn clear(&self) {
+        self.inner.clear().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future::{Ready, ready};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn runs_stored_initializer_after_clear() {
+        let calls = AtomicUsize::new(0);
+        let global = GlobalMockableLazy::const_new(|| async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Arc::new(1)
+        });
+
+        assert_eq!(*global.get().await, 1);
+        global.set(Arc::new(99)).await;
+        assert_eq!(*global.get().await, 99);
+
+        global.clear().await;
+        assert_eq!(*global.get().await, 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    fn default_value() -> Ready<Arc<usize>> {
+        ready(Arc::new(7))
+    }
+
+    static STATIC_LAZY: GlobalMockableLazy<usize, fn() -> Ready<Arc<usize>>> =
+        GlobalMockableLazy::const_new(default_value);
+
+    #[tokio::test]
+    async fn usable_in_static_with_nameable_initializer() {
+        assert_eq!(*STATIC_LAZY.get().await, 7);
+    }
+}
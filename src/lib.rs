@@ -1,131 +1,3095 @@
-use std::sync::Arc;
+use std::convert::Infallible;
 use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
 
-use tokio::sync::{OnceCell, RwLock};
+use tokio::runtime::{Handle, RuntimeFlavor};
+use tokio::sync::watch;
+use tokio_stream::wrappers::WatchStream;
+use tokio_stream::{Stream, StreamExt};
 
-pub struct GlobalMockable<T>
+use crate::sync::{Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+mod any;
+mod cell;
+mod error;
+mod factory;
+mod guard;
+mod init_graph;
+mod keyed;
+mod lazy;
+mod local;
+mod pin;
+mod registry;
+mod retry;
+mod scope;
+mod sync;
+#[cfg(feature = "lock-watchdog")]
+pub mod watchdog;
+
+pub use any::AnyGlobalMockable;
+pub use cell::{ArcSwapCell, Cell};
+pub use error::{GlobalMockableError, TryInitError};
+pub use factory::{FactoryBackedMockable, FactoryRegistry};
+#[cfg(feature = "macros")]
+pub use global_mockable_macros::mockable;
+pub use guard::{RestoreOnDrop, SwapGuard};
+pub use keyed::KeyedGlobalMockable;
+pub use lazy::GlobalMockableLazy;
+pub use local::LocalGlobalMockable;
+pub use pin::PinGlobalMockable;
+pub use registry::{MockableSnapshot, MockableState, Registration, init_all, snapshot_all};
+pub use retry::RetryPolicy;
+pub use scope::{Override, scoped};
+
+#[doc(hidden)]
+pub use inventory;
+#[doc(hidden)]
+pub use paste;
+
+// Lets `define_global_mockable!` make its trait type depend on the
+// dyn-compatibility probe.
+#[doc(hidden)]
+pub mod __private {
+    pub struct Probed<const CHECKED: usize>;
+
+    pub trait Probe<T: ?Sized> {
+        type Checked: ?Sized;
+    }
+
+    impl<T: ?Sized, const CHECKED: usize> Probe<T> for Probed<CHECKED> {
+        type Checked = T;
+    }
+}
+
+pub type BoxInitFuture<T> = Pin<Box<dyn Future<Output = Arc<T>> + Send>>;
+
+/// A type-erased default initializer, see
+/// [`set_default_boxed`](GlobalMockable::set_default_boxed).
+pub type BoxedInit<T> = Box<dyn Fn() -> BoxInitFuture<T> + Send + Sync>;
+
+/// Where the current value of a [`GlobalMockable`] came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Origin {
+    /// Built by an initializer, e.g. in [`get_or_init`](GlobalMockable::get_or_init).
+    Default,
+    /// Installed by the caller, e.g. with [`set`](GlobalMockable::set).
+    Explicit,
+}
+
+/// What [`GlobalMockable::clear`] discards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClearPolicy {
+    /// Any value; the next read rebuilds the default.
+    #[default]
+    All,
+    /// Only values built by an initializer, so an installed mock stays until
+    /// [`clear_all_including_mocks`](GlobalMockable::clear_all_including_mocks).
+    DefaultsOnly,
+}
+
+/// A global slot for a mockable value.
+///
+/// The value itself lives in a [`Cell`], [`ArcSwapCell`] unless another
+/// backend is chosen with [`with_cell`](Self::with_cell).
+///
+/// # Races between writers and initializers
+///
+/// Writes such as `set` and `clear` never wait for a running initializer, and
+/// an initializer's value is only installed if the instance is still empty
+/// when it finishes. So:
+///
+/// - a `set` during initialization wins: the initializer's value is discarded
+///   and every waiting `get_or_init` returns the `set` value;
+/// - a `clear` during initialization has nothing to clear, and the
+///   initializer's value is installed once ready.
+pub struct GlobalMockable<T, C = ArcSwapCell<Arc<T>>>
 where
     T: ?Sized + Send + Sync,
 {
-    instance: RwLock<OnceCell<Arc<T>>>,
+    // Serializes writers to `cell`. Readers that must not observe a
+    // replacement in progress, or that need `frozen` and `from_default` to
+    // agree with the value, hold it shared.
+    lock: RwLock<()>,
+    // Serializes default initialization without holding `lock`, so a
+    // factory can read other globals and a concurrent `set` is never blocked.
+    init: Mutex<()>,
+    // Only written while holding the write lock.
+    cell: C,
+    // Only flipped while holding the write lock.
+    frozen: AtomicBool,
+    // Whether `cell` holds an initializer's value; guarded by the lock.
+    from_default: AtomicBool,
+    // Bumped on every change to `cell`, while holding the write lock.
+    generation: AtomicU64,
+    // `ClearPolicy::DefaultsOnly` is in effect.
+    sticky_mocks: AtomicBool,
+    default: std::sync::Mutex<Option<Arc<BoxedInit<T>>>>,
+    // Created by the first `subscribe`; notified on every change after that.
+    updates: std::sync::OnceLock<watch::Sender<Option<Arc<T>>>>,
+    // Empty, and so unallocated, until the first `on_clear`.
+    clear_callbacks: std::sync::Mutex<Vec<ClearCallback>>,
+    // A restore a dropped `SwapGuard` left to whoever releases the lock next.
+    deferred: std::sync::Mutex<Option<DeferredRestore<T>>>,
 }
 
+type ClearCallback = Box<dyn Fn() + Send + Sync>;
+
 impl<T> GlobalMockable<T>
 where
     T: ?Sized + Send + Sync,
 {
     pub const fn const_new() -> Self {
+        Self::with_cell(ArcSwapCell::new())
+    }
+
+    /// Creates an instance to share through an `Arc` instead of a `static`,
+    /// e.g. one per service container.
+    pub fn new_arc() -> Arc<Self> {
+        Arc::new(Self::const_new())
+    }
+}
+
+impl<T, C> GlobalMockable<T, C>
+where
+    T: ?Sized + Send + Sync,
+    C: Cell<Arc<T>>,
+{
+    /// Creates an empty instance storing its value in `cell`.
+    pub const fn with_cell(cell: C) -> Self {
         GlobalMockable {
-            instance: RwLock::const_new(OnceCell::const_new()),
+            lock: RwLock::const_new(()),
+            init: Mutex::const_new(()),
+            cell,
+            frozen: AtomicBool::new(false),
+            from_default: AtomicBool::new(false),
+            generation: AtomicU64::new(0),
+            sticky_mocks: AtomicBool::new(false),
+            default: std::sync::Mutex::new(None),
+            updates: std::sync::OnceLock::new(),
+            clear_callbacks: std::sync::Mutex::new(Vec::new()),
+            deferred: std::sync::Mutex::new(None),
+        }
+    }
+
+    /// Returns the current value, running `f` to initialize it if empty.
+    ///
+    /// Only one initializer runs at a time, and no lock other than this
+    /// global's init lock is held while it does, so `f` may read other
+    /// globals. If `f` (transitively) needs this global's own value the
+    /// initialization can never finish; that is detected and panics rather
+    /// than deadlocking, including across tasks initializing concurrently.
+    ///
+    /// A value `set` while `f` is running takes precedence over its result.
+    ///
+    /// Neither `f` nor its future need to be `'static`: only the produced
+    /// `Arc` is stored, so the initializer may borrow from the caller.
+    ///
+    /// # Cancellation
+    ///
+    /// Dropping the returned future while `f` is running drops `f`'s future
+    /// too: nothing is cached and the init lock is released, so the next
+    /// caller runs its own initializer as if the first attempt never started.
+    ///
+    /// # Panics
+    ///
+    /// Panics in the cases where [`try_get_or_init`](Self::try_get_or_init)
+    /// returns an error.
+    pub async fn get_or_init<F, Fut>(&self, f: F) -> Arc<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Arc<T>> + Send,
+    {
+        self.try_get_or_init(f)
+            .await
+            .unwrap_or_else(|err| self.fail(err))
+    }
+
+    /// Like [`get_or_init`](Self::get_or_init), also reporting whether this
+    /// call initialized the instance. Of concurrent callers on an empty
+    /// instance, only the one whose initializer's value got installed sees
+    /// `true`; if a `set` wins the race, nobody does.
+    pub async fn get_or_init_reporting<F, Fut>(&self, f: F) -> (Arc<T>, bool)
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Arc<T>> + Send,
+    {
+        let result = self
+            .init_reporting(|| {
+                let init = f();
+                async move { Ok::<_, Infallible>(init.await) }
+            })
+            .await
+            .unwrap_or_else(|err| self.fail(err));
+        result.unwrap_or_else(|never| match never {})
+    }
+
+    /// Like [`get_or_init`](Self::get_or_init), taking an async closure.
+    ///
+    /// The returned future is `Send` only if `f`'s future is.
+    #[cfg(feature = "async-closures")]
+    pub async fn get_or_init_async<F>(&self, f: F) -> Arc<T>
+    where
+        F: AsyncFnOnce() -> Arc<T>,
+    {
+        let result = self
+            .init_with(|| async move { Ok::<_, Infallible>(f().await) })
+            .await
+            .unwrap_or_else(|err| self.fail(err));
+        result.unwrap_or_else(|never| match never {})
+    }
+
+    /// Like [`get_or_init`](Self::get_or_init), but reports the states in
+    /// which no value can be produced instead of panicking.
+    pub async fn try_get_or_init<F, Fut>(&self, f: F) -> Result<Arc<T>, GlobalMockableError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Arc<T>> + Send,
+    {
+        let result = self
+            .init_with(|| {
+                let init = f();
+                async move { Ok::<_, Infallible>(init.await) }
+            })
+            .await?;
+        Ok(result.unwrap_or_else(|never| match never {}))
+    }
+
+    /// Like [`get_or_init`](Self::get_or_init), for initializers that can
+    /// fail. A failed attempt leaves the instance empty, so the next caller
+    /// runs its own initializer. Callers that were waiting on a failed attempt
+    /// never see its error; each gets the outcome of its own turn instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics in the cases where [`try_get_or_init`](Self::try_get_or_init)
+    /// returns an error.
+    pub async fn get_or_try_init<F, Fut, E>(&self, f: F) -> Result<Arc<T>, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Arc<T>, E>> + Send,
+    {
+        self.init_with(f).await.unwrap_or_else(|err| self.fail(err))
+    }
+
+    /// Like [`get_or_try_init`](Self::get_or_try_init), returning the states
+    /// in which no value can be produced as [`TryInitError::Mockable`] instead
+    /// of panicking.
+    pub async fn try_get_or_try_init<F, Fut, E>(&self, f: F) -> Result<Arc<T>, TryInitError<E>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Arc<T>, E>> + Send,
+    {
+        self.init_with(f).await?.map_err(TryInitError::Init)
+    }
+
+    /// Computes something from the current value, initializing it with `init`
+    /// if empty. Unlike `get_or_init`, reading an existing value clones no
+    /// `Arc`; keep `f` short, as the value is borrowed from the cell while it
+    /// runs.
+    ///
+    /// # Panics
+    ///
+    /// Panics like [`get_or_init`](Self::get_or_init).
+    pub async fn with_or_init<F, Fut, M, R>(&self, init: F, f: M) -> R
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Arc<T>> + Send,
+        M: FnOnce(&T) -> R,
+    {
+        let f = match self.cell.with(|value| match value {
+            Some(value) => Ok(f(value)),
+            None => Err(f),
+        }) {
+            Ok(result) => return result,
+            Err(f) => f,
+        };
+        f(&*self.get_or_init(init).await)
+    }
+
+    /// Like [`get_or_init`](Self::get_or_init), for an initializing future
+    /// that already exists. If the instance already holds a value, `fut` is
+    /// dropped without ever being polled.
+    pub async fn get_or_init_future<Fut>(&self, fut: Fut) -> Arc<T>
+    where
+        Fut: Future<Output = Arc<T>> + Send,
+    {
+        self.get_or_init(|| fut).await
+    }
+
+    /// Like [`get_or_init`](Self::get_or_init), for initializers that need
+    /// nothing to await.
+    pub async fn get_or_init_sync<F>(&self, f: F) -> Arc<T>
+    where
+        F: FnOnce() -> Arc<T>,
+    {
+        self.get_or_init(|| std::future::ready(f())).await
+    }
+
+    /// Returns the current value without ever running a default, for code
+    /// paths where a missing value means start-up forgot to install it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance is empty.
+    pub async fn current_or_panic(&self) -> Arc<T> {
+        self.try_current().await.unwrap_or_else(|_| {
+            panic!(
+                "GlobalMockable<{}> was never initialized; call set() during startup",
+                std::any::type_name::<T>()
+            )
+        })
+    }
+
+    /// Like [`current_or_panic`](Self::current_or_panic), returning
+    /// [`GlobalMockableError::Uninitialized`] instead of panicking.
+    pub async fn try_current(&self) -> Result<Arc<T>, GlobalMockableError> {
+        self.load().await.ok_or(GlobalMockableError::Uninitialized)
+    }
+
+    /// Initializes the instance with `f` if empty and exercises the read
+    /// path once, so the first request served doesn't pay for either.
+    ///
+    /// # Panics
+    ///
+    /// Panics like [`get_or_init`](Self::get_or_init).
+    pub async fn warm_up<F, Fut>(&self, f: F)
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Arc<T>> + Send,
+    {
+        self.get_or_init(f).await;
+        drop(self.load().await);
+    }
+
+    /// Whether the instance currently holds a value. Doesn't wait for a
+    /// write in progress.
+    pub fn is_initialized(&self) -> bool {
+        self.cached().is_some()
+    }
+
+    /// Returns the current value, installing `default` if empty.
+    pub async fn get_or_default(&self, default: Arc<T>) -> Arc<T> {
+        self.get_or_init(|| async move { default }).await
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the instance is [frozen](Self::freeze).
+    pub async fn set(&self, value: Arc<T>) {
+        self.try_set(value)
+            .await
+            .unwrap_or_else(|err| self.fail(err));
+    }
+
+    /// Like [`set`](Self::set), returning [`GlobalMockableError::Frozen`]
+    /// instead of panicking.
+    pub async fn try_set(&self, value: Arc<T>) -> Result<(), GlobalMockableError> {
+        let write = self.write().await;
+        if self.is_frozen() {
+            return Err(GlobalMockableError::Frozen);
+        }
+        self.store(&write, Some(value));
+        Ok(())
+    }
+
+    /// Installs a boxed value, such as a `Box<dyn Trait>` from a factory,
+    /// converting it to an `Arc`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance is [frozen](Self::freeze).
+    pub async fn set_boxed(&self, value: Box<T>) {
+        self.set(Arc::from(value)).await;
+    }
+
+    /// Builds a value with `f`, such as a mock that has to open a connection,
+    /// and installs it.
+    ///
+    /// `f` runs before the write lock is taken, so readers keep seeing the
+    /// previous value however long it takes, and a `set` that lands meanwhile
+    /// is overwritten once `f` finishes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance is [frozen](Self::freeze).
+    pub async fn set_async<F, Fut>(&self, f: F)
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Arc<T>>,
+    {
+        let value = f().await;
+        self.set(value).await;
+    }
+
+    /// Installs `value` unless it equals the current value, returning whether
+    /// it did. Subscribers are not notified of a no-op.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance is [frozen](Self::freeze) and `value` differs.
+    pub async fn set_if_changed(&self, value: Arc<T>) -> bool
+    where
+        T: PartialEq,
+    {
+        self.try_set_if_changed(value)
+            .await
+            .unwrap_or_else(|err| self.fail(err))
+    }
+
+    /// Like [`set_if_changed`](Self::set_if_changed), returning
+    /// [`GlobalMockableError::Frozen`] instead of panicking.
+    pub async fn try_set_if_changed(&self, value: Arc<T>) -> Result<bool, GlobalMockableError>
+    where
+        T: PartialEq,
+    {
+        let write = self.write().await;
+        if self.cell.get().as_deref() == Some(&*value) {
+            return Ok(false);
+        }
+        self.check_not_frozen()?;
+        self.store(&write, Some(value));
+        Ok(true)
+    }
+
+    /// Installs `value` and returns the value it replaced, if any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance is [frozen](Self::freeze).
+    #[must_use]
+    pub async fn replace_arc(&self, value: Arc<T>) -> Option<Arc<T>> {
+        self.try_replace_arc(value)
+            .await
+            .unwrap_or_else(|err| self.fail(err))
+    }
+
+    /// Like [`replace_arc`](Self::replace_arc), returning
+    /// [`GlobalMockableError::Frozen`] instead of panicking.
+    pub async fn try_replace_arc(
+        &self,
+        value: Arc<T>,
+    ) -> Result<Option<Arc<T>>, GlobalMockableError> {
+        let write = self.write().await;
+        self.check_not_frozen()?;
+        let previous = self.cell.get();
+        self.store(&write, Some(value));
+        Ok(previous)
+    }
+
+    /// Installs `value` until the returned guard is restored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance is [frozen](Self::freeze).
+    pub async fn swap(&self, value: Arc<T>) -> SwapGuard<'_, T, C> {
+        self.try_swap(value)
+            .await
+            .unwrap_or_else(|err| self.fail(err))
+    }
+
+    /// Like [`swap`](Self::swap), returning [`GlobalMockableError::Frozen`]
+    /// instead of panicking.
+    pub async fn try_swap(
+        &self,
+        value: Arc<T>,
+    ) -> Result<SwapGuard<'_, T, C>, GlobalMockableError> {
+        let write = self.write().await;
+        self.check_not_frozen()?;
+        let previous = self.cell.get();
+        let origin = self.stored_origin();
+        self.store(&write, Some(value));
+        Ok(SwapGuard::new(self, previous, origin))
+    }
+
+    /// Installs `value` until the returned guard is dropped, for async tests
+    /// that cannot await a `restore` on every exit path.
+    ///
+    /// The guard restores the previous value on the spot if the lock is free,
+    /// which needs no runtime at all. Otherwise it spawns the restore onto
+    /// the runtime `set_scoped` ran on, which skips it if the value changes
+    /// in the meantime; if that runtime is shutting down or gone, the restore
+    /// is dropped with it and the value stays installed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance is [frozen](Self::freeze).
+    pub async fn set_scoped(&'static self, value: Arc<T>) -> RestoreOnDrop<T, C>
+    where
+        T: 'static,
+        C: 'static,
+    {
+        self.try_set_scoped(value)
+            .await
+            .unwrap_or_else(|err| self.fail(err))
+    }
+
+    /// Like [`set_scoped`](Self::set_scoped), returning
+    /// [`GlobalMockableError::Frozen`] instead of panicking.
+    pub async fn try_set_scoped(
+        &'static self,
+        value: Arc<T>,
+    ) -> Result<RestoreOnDrop<T, C>, GlobalMockableError>
+    where
+        T: 'static,
+        C: 'static,
+    {
+        let write = self.write().await;
+        self.check_not_frozen()?;
+        let previous = self.cell.get();
+        let origin = self.stored_origin();
+        self.store(&write, Some(value));
+        Ok(RestoreOnDrop::new(self, previous, origin))
+    }
+
+    /// Installs `value` only if `pred` accepts the current value, returning
+    /// whether it did.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance is [frozen](Self::freeze) and `pred` accepts.
+    pub async fn replace_if<P>(&self, pred: P, value: Arc<T>) -> bool
+    where
+        P: FnOnce(Option<&Arc<T>>) -> bool,
+    {
+        self.try_replace_if(pred, value)
+            .await
+            .unwrap_or_else(|err| self.fail(err))
+    }
+
+    /// Like [`replace_if`](Self::replace_if), returning
+    /// [`GlobalMockableError::Frozen`] instead of panicking.
+    pub async fn try_replace_if<P>(
+        &self,
+        pred: P,
+        value: Arc<T>,
+    ) -> Result<bool, GlobalMockableError>
+    where
+        P: FnOnce(Option<&Arc<T>>) -> bool,
+    {
+        let write = self.write().await;
+        if !pred(self.cell.get().as_ref()) {
+            return Ok(false);
+        }
+        self.check_not_frozen()?;
+        self.store(&write, Some(value));
+        Ok(true)
+    }
+
+    /// Discards the value as chosen by the [`ClearPolicy`], everything by
+    /// default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance is [frozen](Self::freeze) and would be cleared.
+    pub async fn clear(&self) {
+        self.try_clear().await.unwrap_or_else(|err| self.fail(err));
+    }
+
+    /// Like [`clear`](Self::clear), returning [`GlobalMockableError::Frozen`]
+    /// instead of panicking.
+    pub async fn try_clear(&self) -> Result<(), GlobalMockableError> {
+        let write = self.write().await;
+        let sticky = self.sticky_mocks.load(Ordering::Relaxed);
+        if sticky && (self.cell.get().is_none() || !self.from_default.load(Ordering::Relaxed)) {
+            return Ok(());
+        }
+        if self.is_frozen() {
+            return Err(GlobalMockableError::Frozen);
+        }
+        self.store(&write, None);
+        Ok(())
+    }
+
+    /// Discards any value, regardless of the [`ClearPolicy`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance is [frozen](Self::freeze).
+    pub async fn clear_all_including_mocks(&self) {
+        self.try_clear_all_including_mocks()
+            .await
+            .unwrap_or_else(|err| self.fail(err));
+    }
+
+    /// Like [`clear_all_including_mocks`](Self::clear_all_including_mocks),
+    /// returning [`GlobalMockableError::Frozen`] instead of panicking.
+    pub async fn try_clear_all_including_mocks(&self) -> Result<(), GlobalMockableError> {
+        let write = self.write().await;
+        self.check_not_frozen()?;
+        self.store(&write, None);
+        Ok(())
+    }
+
+    /// Discards any value without waiting, for teardown paths that cannot
+    /// await or risk blocking, such as a panic hook or `Drop`.
+    ///
+    /// Returns `false`, leaving the value in place, if another task holds the
+    /// lock or the instance is [frozen](Self::freeze). Like
+    /// [`clear_all_including_mocks`](Self::clear_all_including_mocks), it
+    /// ignores the [`ClearPolicy`].
+    pub fn try_clear_sync(&self) -> bool {
+        let Ok(write) = self.lock.try_write() else {
+            return false;
+        };
+        if self.is_frozen() {
+            return false;
         }
+        self.store(&write, None);
+        true
+    }
+
+    pub fn set_clear_policy(&self, policy: ClearPolicy) {
+        self.sticky_mocks
+            .store(policy == ClearPolicy::DefaultsOnly, Ordering::Relaxed);
+    }
+
+    /// Blocking variant of [`get_or_init`](Self::get_or_init), for synchronous
+    /// code running inside a Tokio runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics like [`set_blocking`](Self::set_blocking), or if `f` needs this
+    /// instance's own value.
+    pub fn get_or_init_blocking<F, Fut>(&self, f: F) -> Arc<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Arc<T>> + Send,
+    {
+        self.block_on("get", self.get_or_init(f))
+    }
+
+    /// Like [`get_or_init_blocking`](Self::get_or_init_blocking), returning
+    /// an error instead of panicking, including
+    /// [`GlobalMockableError::NoRuntime`] outside a multi-thread runtime.
+    pub fn try_get_or_init_blocking<F, Fut>(&self, f: F) -> Result<Arc<T>, GlobalMockableError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Arc<T>> + Send,
+    {
+        self.try_block_on(self.try_get_or_init(f))?
+    }
+
+    /// Blocking variant of [`set`](Self::set), for synchronous code running
+    /// inside a Tokio runtime, such as non-async test fixtures.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no Tokio runtime is running, if the runtime is
+    /// current-thread (which cannot block without stalling itself), or if the
+    /// instance is [frozen](Self::freeze).
+    pub fn set_blocking(&self, value: Arc<T>) {
+        self.block_on("set", self.set(value));
+    }
+
+    /// Blocking variant of [`clear`](Self::clear); panics like
+    /// [`set_blocking`](Self::set_blocking).
+    pub fn clear_blocking(&self) {
+        self.block_on("clear", self.clear());
+    }
+
+    /// Like [`set_blocking`](Self::set_blocking), returning an error instead
+    /// of panicking, including [`GlobalMockableError::NoRuntime`] outside a
+    /// multi-thread runtime.
+    pub fn try_set_blocking(&self, value: Arc<T>) -> Result<(), GlobalMockableError> {
+        self.try_block_on(self.try_set(value))?
+    }
+
+    /// Like [`clear_blocking`](Self::clear_blocking), returning an error
+    /// instead of panicking.
+    pub fn try_clear_blocking(&self) -> Result<(), GlobalMockableError> {
+        self.try_block_on(self.try_clear())?
+    }
+
+    /// Clears the instance only if its value came from an initializer, leaving
+    /// an explicitly installed value alone. Returns whether it cleared.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance is [frozen](Self::freeze) and would be cleared.
+    pub async fn clear_if_default(&self) -> bool {
+        self.try_clear_if_default()
+            .await
+            .unwrap_or_else(|err| self.fail(err))
+    }
+
+    /// Like [`clear_if_default`](Self::clear_if_default), returning
+    /// [`GlobalMockableError::Frozen`] instead of panicking.
+    pub async fn try_clear_if_default(&self) -> Result<bool, GlobalMockableError> {
+        let write = self.write().await;
+        if self.cell.get().is_none() || !self.from_default.load(Ordering::Relaxed) {
+            return Ok(false);
+        }
+        self.check_not_frozen()?;
+        self.store(&write, None);
+        Ok(true)
+    }
+
+    /// Where the current value came from, or `None` if empty.
+    #[must_use]
+    pub async fn origin(&self) -> Option<Origin> {
+        let _read = self.read().await;
+        self.cell.get().map(|_| self.stored_origin())
+    }
+
+    /// A counter bumped by every change to the value, including
+    /// initialization and `clear`. Comparing two readings tells whether the
+    /// value may have been swapped in between, without comparing values.
+    #[must_use]
+    pub async fn generation(&self) -> u64 {
+        let _read = self.read().await;
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    /// Drops the current value and replaces it with a fresh one from `f`,
+    /// which is returned.
+    ///
+    /// Unlike a `clear` followed by `get_or_init`, no caller can observe the
+    /// instance empty in between: readers wait for `f` to finish.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance is [frozen](Self::freeze) or `f` needs this
+    /// instance's own value.
+    pub async fn clear_and_get<F, Fut>(&self, f: F) -> Arc<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Arc<T>> + Send,
+    {
+        self.try_clear_and_get(f)
+            .await
+            .unwrap_or_else(|err| self.fail(err))
+    }
+
+    /// Like [`clear_and_get`](Self::clear_and_get), returning an error
+    /// instead of panicking.
+    pub async fn try_clear_and_get<F, Fut>(&self, f: F) -> Result<Arc<T>, GlobalMockableError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Arc<T>> + Send,
+    {
+        self.try_replace_with(
+            |previous| {
+                drop(previous);
+                f()
+            },
+            Origin::Default,
+        )
+        .await
+    }
+
+    /// Replaces the current value (or `None`) with the one `f` computes from
+    /// it, and returns the replacement.
+    ///
+    /// The whole update is atomic: `set`, `clear` and readers all wait until
+    /// `f` finishes, so nothing can observe or overwrite the old value in the
+    /// meantime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance is [frozen](Self::freeze) or `f` needs this
+    /// instance's own value.
+    pub async fn swap_with<F, Fut>(&self, f: F) -> Arc<T>
+    where
+        F: FnOnce(Option<Arc<T>>) -> Fut,
+        Fut: Future<Output = Arc<T>> + Send,
+    {
+        self.try_swap_with(f)
+            .await
+            .unwrap_or_else(|err| self.fail(err))
+    }
+
+    /// Like [`swap_with`](Self::swap_with), returning an error instead of
+    /// panicking.
+    pub async fn try_swap_with<F, Fut>(&self, f: F) -> Result<Arc<T>, GlobalMockableError>
+    where
+        F: FnOnce(Option<Arc<T>>) -> Fut,
+        Fut: Future<Output = Arc<T>> + Send,
+    {
+        self.try_replace_with(f, Origin::Explicit).await
+    }
+
+    /// Installs `wrap`'s decoration of the current value, initializing it with
+    /// `init` first if the instance is empty. This is how a spy that delegates
+    /// to the real implementation while recording calls gets installed.
+    ///
+    /// The wrapper counts as an explicitly set value, so `clear` removes it
+    /// together with the value it wraps.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance is [frozen](Self::freeze) or `init` needs this
+    /// instance's own value.
+    pub async fn wrap_current<I, Fut, W>(&self, init: I, wrap: W) -> Arc<T>
+    where
+        I: FnOnce() -> Fut + Send,
+        Fut: Future<Output = Arc<T>> + Send,
+        W: FnOnce(Arc<T>) -> Arc<T> + Send,
+    {
+        self.try_wrap_current(init, wrap)
+            .await
+            .unwrap_or_else(|err| self.fail(err))
+    }
+
+    /// Like [`wrap_current`](Self::wrap_current), returning an error instead
+    /// of panicking.
+    pub async fn try_wrap_current<I, Fut, W>(
+        &self,
+        init: I,
+        wrap: W,
+    ) -> Result<Arc<T>, GlobalMockableError>
+    where
+        I: FnOnce() -> Fut + Send,
+        Fut: Future<Output = Arc<T>> + Send,
+        W: FnOnce(Arc<T>) -> Arc<T> + Send,
+    {
+        self.try_replace_with(
+            |previous| async move {
+                let current = match previous {
+                    Some(current) => current,
+                    None => init().await,
+                };
+                wrap(current)
+            },
+            Origin::Explicit,
+        )
+        .await
+    }
+
+    async fn try_replace_with<F, Fut>(
+        &self,
+        f: F,
+        origin: Origin,
+    ) -> Result<Arc<T>, GlobalMockableError>
+    where
+        F: FnOnce(Option<Arc<T>>) -> Fut,
+        Fut: Future<Output = Arc<T>> + Send,
+    {
+        let permit = self.begin_init().await?;
+        let write = self.write().await;
+        self.check_not_frozen()?;
+        // Readers now block on the init permit until the replacement lands,
+        // and subscribers only hear about the replacement.
+        let previous = self.cell.take();
+        let mut put_back = PutBack {
+            cell: &self.cell,
+            value: previous.clone(),
+        };
+
+        let value = permit.run(f(previous)).await;
+        put_back.value = None;
+        self.store_from(&write, Arc::clone(&value), origin);
+        Ok(value)
+    }
+
+    /// Permanently locks in the current state: `set` and `clear` panic from
+    /// now on, and an empty instance stays empty.
+    pub async fn freeze(&self) {
+        let _write = self.write().await;
+        self.frozen.store(true, Ordering::Release);
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen.load(Ordering::Acquire)
+    }
+
+    /// Returns a weak reference to the current value, if any, which does not
+    /// keep it alive past a `clear` or `set`.
+    #[must_use]
+    pub async fn downgrade(&self) -> Option<Weak<T>> {
+        self.load().await.as_ref().map(Arc::downgrade)
+    }
+
+    /// Like [`downgrade`](Self::downgrade), initializing the instance with `f`
+    /// first if it is empty, so there is always a value to observe.
+    ///
+    /// # Panics
+    ///
+    /// Panics like [`get_or_init`](Self::get_or_init).
+    #[must_use]
+    pub async fn get_weak_or_init<F, Fut>(&self, f: F) -> Weak<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Arc<T>> + Send,
+    {
+        Arc::downgrade(&self.get_or_init(f).await)
+    }
+
+    /// Registers an initializer chosen at runtime, which the
+    /// [`define_global_mockable!`] accessors prefer over their static default.
+    pub fn set_default_boxed(&self, f: BoxedInit<T>) {
+        *self.lock_default() = Some(Arc::new(f));
+    }
+
+    /// Registers `f` like [`set_default_boxed`](Self::set_default_boxed) and
+    /// replaces the current value with a fresh one from it, in one step:
+    /// readers wait and then see the new value, never the old default or an
+    /// empty instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instance is [frozen](Self::freeze) or `f` needs this
+    /// instance's own value.
+    pub async fn replace_default_and_reinit(&self, f: BoxedInit<T>) -> Arc<T> {
+        self.try_replace_default_and_reinit(f)
+            .await
+            .unwrap_or_else(|err| self.fail(err))
+    }
+
+    /// Like [`replace_default_and_reinit`](Self::replace_default_and_reinit),
+    /// returning an error instead of panicking. The initializer is not
+    /// registered if the replacement cannot start.
+    pub async fn try_replace_default_and_reinit(
+        &self,
+        f: BoxedInit<T>,
+    ) -> Result<Arc<T>, GlobalMockableError> {
+        let init = Arc::new(f);
+        self.try_replace_with(
+            |previous| {
+                drop(previous);
+                *self.lock_default() = Some(Arc::clone(&init));
+                init()
+            },
+            Origin::Default,
+        )
+        .await
+    }
+
+    #[must_use]
+    pub fn registered_default(&self) -> Option<Arc<BoxedInit<T>>> {
+        self.lock_default().clone()
+    }
+
+    /// Returns an owned copy of the current value, if any, detached from the
+    /// instance.
+    #[must_use]
+    pub async fn clone_inner(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.load().await.as_deref().cloned()
+    }
+
+    /// Returns a receiver that observes every change to the current value.
+    pub async fn subscribe(&self) -> watch::Receiver<Option<Arc<T>>> {
+        let _read = self.read().await;
+        self.updates
+            .get_or_init(|| watch::channel(self.cell.get()).0)
+            .subscribe()
+    }
+
+    /// Returns the current value, initializing it with `f` if empty, together
+    /// with a stream of every value installed after it.
+    ///
+    /// Both are taken under one read lock, so no update between them is
+    /// missed. Clears are not part of the stream, and if one lands right
+    /// after initialization, the initialized value is still returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics like [`get_or_init`](Self::get_or_init).
+    pub async fn subscribe_current<F, Fut>(
+        &self,
+        f: F,
+    ) -> (Arc<T>, impl Stream<Item = Arc<T>> + use<T, C, F, Fut>)
+    where
+        T: 'static,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Arc<T>> + Send,
+    {
+        let initialized = self.get_or_init(f).await;
+
+        let _read = self.read().await;
+        let current = self.cell.get().unwrap_or(initialized);
+        let receiver = self
+            .updates
+            .get_or_init(|| watch::channel(self.cell.get()).0)
+            .subscribe();
+        (
+            current,
+            WatchStream::from_changes(receiver).filter_map(|value| value),
+        )
+    }
+
+    /// Registers `cb` to run whenever a value is cleared, but not when it is
+    /// replaced, so layers caching something derived from the value can purge
+    /// it. Callbacks run while the write lock is held and must not wait on
+    /// this instance.
+    pub async fn on_clear<F>(&self, cb: F)
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.lock_clear_callbacks().push(Box::new(cb));
+    }
+
+    /// Creates an independent instance holding the current value, if any.
+    pub async fn fork(&self) -> GlobalMockable<T, C>
+    where
+        C: Default,
+    {
+        let fork = GlobalMockable::with_cell(C::default());
+        if let Some(current) = self.load().await {
+            fork.set(current).await;
+        }
+        fork
+    }
+
+    fn assert_not_frozen(&self) {
+        self.check_not_frozen().unwrap_or_else(|err| self.fail(err));
+    }
+
+    fn check_not_frozen(&self) -> Result<(), GlobalMockableError> {
+        if self.is_frozen() {
+            return Err(GlobalMockableError::Frozen);
+        }
+        Ok(())
+    }
+
+    async fn init_with<F, Fut, E>(&self, f: F) -> Result<Result<Arc<T>, E>, GlobalMockableError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Arc<T>, E>>,
+    {
+        let result = self.init_reporting(f).await?;
+        Ok(result.map(|(value, _)| value))
+    }
+
+    // Also reports whether the returned value is the one `f` produced.
+    async fn init_reporting<F, Fut, E>(
+        &self,
+        f: F,
+    ) -> Result<Result<(Arc<T>, bool), E>, GlobalMockableError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Arc<T>, E>>,
+    {
+        if let Some(cached) = self.cached() {
+            return Ok(Ok((cached, false)));
+        }
+        if self.is_frozen() {
+            return Err(GlobalMockableError::Frozen);
+        }
+
+        let permit = self.begin_init().await?;
+
+        // Another caller may have finished initializing while we waited.
+        if let Some(cached) = self.cached() {
+            return Ok(Ok((cached, false)));
+        }
+
+        let value = match permit.run(f()).await {
+            Ok(value) => value,
+            Err(err) => return Ok(Err(err)),
+        };
+
+        let write = self.write().await;
+        if let Some(current) = self.cell.get() {
+            return Ok(Ok((current, false)));
+        }
+        if self.is_frozen() {
+            return Err(GlobalMockableError::Frozen);
+        }
+        self.store_from(&write, Arc::clone(&value), Origin::Default);
+        Ok(Ok((value, true)))
+    }
+
+    fn fail(&self, err: GlobalMockableError) -> ! {
+        panic!("GlobalMockable<{}>: {err}", std::any::type_name::<T>())
+    }
+
+    async fn begin_init(&self) -> Result<InitPermit<'_>, GlobalMockableError> {
+        let chain = init_graph::current_chain().unwrap_or_else(init_graph::new_chain);
+        let waiting = init_graph::wait_for(chain, self.id()).ok_or(GlobalMockableError::Cycle)?;
+        let guard = self.acquire("init", self.init.lock()).await;
+        Ok(InitPermit {
+            chain,
+            _holding: waiting.acquired(),
+            _guard: guard,
+        })
+    }
+
+    async fn read(&self) -> ReadGuard<'_, T, C> {
+        ReadGuard {
+            mockable: self,
+            guard: Some(self.acquire("read", self.lock.read()).await),
+        }
+    }
+
+    async fn write(&self) -> WriteGuard<'_, T, C> {
+        WriteGuard {
+            mockable: self,
+            guard: self.acquire("write", self.lock.write()).await,
+        }
+    }
+
+    // The current value, waiting out a replacement in progress.
+    async fn load(&self) -> Option<Arc<T>> {
+        let _read = self.read().await;
+        self.cell.get()
+    }
+
+    async fn acquire<Fut: Future>(&self, lock: &'static str, acquire: Fut) -> Fut::Output {
+        #[cfg(feature = "lock-watchdog")]
+        {
+            watchdog::watch(std::any::type_name::<T>(), lock, acquire).await
+        }
+        #[cfg(not(feature = "lock-watchdog"))]
+        {
+            let _ = lock;
+            acquire.await
+        }
+    }
+
+    fn lock_default(&self) -> std::sync::MutexGuard<'_, Option<Arc<BoxedInit<T>>>> {
+        self.default
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_deferred(&self) -> std::sync::MutexGuard<'_, Option<DeferredRestore<T>>> {
+        self.deferred
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_clear_callbacks(&self) -> std::sync::MutexGuard<'_, Vec<ClearCallback>> {
+        self.clear_callbacks
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // Where the value in the cell came from, if there is one.
+    fn stored_origin(&self) -> Origin {
+        if self.from_default.load(Ordering::Relaxed) {
+            Origin::Default
+        } else {
+            Origin::Explicit
+        }
+    }
+
+    // Reinstates a value a guard took aside, together with its origin.
+    async fn restore(&self, value: Option<Arc<T>>, origin: Origin) {
+        let write = self.write().await;
+        self.assert_not_frozen();
+        self.store_with(&write, value, origin);
+    }
+
+    fn cached(&self) -> Option<Arc<T>> {
+        self.cell.get()
+    }
+
+    // Stores `value` if nobody holds the lock, returning `None` if somebody
+    // does and `Some(false)` if the instance is frozen.
+    fn try_store_now(&self, value: &Option<Arc<T>>, origin: Origin) -> Option<bool> {
+        let write = self.lock.try_write().ok()?;
+        if self.is_frozen() {
+            return Some(false);
+        }
+        self.store_with(&write, value.clone(), origin);
+        Some(true)
+    }
+
+    // Best-effort write for `Drop` impls, which cannot await. Blocks only where
+    // that cannot stall the runtime, and never overrides a freeze. On a
+    // current-thread runtime a contended write is left to the lock's holder,
+    // which cannot make progress while this thread blocks.
+    fn store_blocking(&self, value: Option<Arc<T>>, origin: Origin) -> bool {
+        let install = |write: &RwLockWriteGuard<'_, ()>| {
+            if self.is_frozen() {
+                return false;
+            }
+            self.store_with(write, value.clone(), origin);
+            true
+        };
+
+        if let Some(stored) = self.try_store_now(&value, origin) {
+            return stored;
+        }
+        match Handle::try_current() {
+            Err(_) => install(&self.lock.blocking_write()),
+            Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+                tokio::task::block_in_place(|| install(&handle.block_on(self.lock.write())))
+            }
+            Ok(_) => {
+                *self.lock_deferred() = Some(self.defer_restore(value, origin));
+                // The holder may have let go before the restore was recorded.
+                if let Ok(write) = self.lock.try_write() {
+                    self.run_deferred(&write);
+                }
+                true
+            }
+        }
+    }
+
+    fn defer_restore(&self, value: Option<Arc<T>>, origin: Origin) -> DeferredRestore<T> {
+        DeferredRestore {
+            value,
+            origin,
+            generation: self.generation.load(Ordering::Relaxed),
+        }
+    }
+
+    fn run_deferred(&self, write: &RwLockWriteGuard<'_, ()>) {
+        if let Some(deferred) = self.lock_deferred().take() {
+            self.store_deferred(write, deferred);
+        }
+    }
+
+    // Makes a restore a guard could not make when dropped, unless the value
+    // changed since; a later write wins over an earlier guard's restore.
+    fn store_deferred(&self, write: &RwLockWriteGuard<'_, ()>, deferred: DeferredRestore<T>) {
+        if self.generation.load(Ordering::Relaxed) != deferred.generation {
+            guard::warn(format_args!(
+                "did not restore GlobalMockable<{}>, which changed after its guard was dropped",
+                std::any::type_name::<T>()
+            ));
+        } else if self.is_frozen() {
+            guard::warn_frozen::<T>();
+        } else {
+            self.store_with(write, deferred.value, deferred.origin);
+        }
+    }
+
+    // `block_in_place` panics on its own on a current-thread runtime; only the
+    // missing runtime needs a friendlier message.
+    fn block_on<Fut: Future>(&self, operation: &str, fut: Fut) -> Fut::Output {
+        let handle = Handle::try_current().unwrap_or_else(|_| {
+            panic!("GlobalMockable::{operation} requires a Tokio runtime; none is running")
+        });
+        tokio::task::block_in_place(|| handle.block_on(fut))
+    }
+
+    fn try_block_on<Fut: Future>(&self, fut: Fut) -> Result<Fut::Output, GlobalMockableError> {
+        match Handle::try_current() {
+            Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+                Ok(tokio::task::block_in_place(|| handle.block_on(fut)))
+            }
+            _ => Err(GlobalMockableError::NoRuntime),
+        }
+    }
+
+    // Taking the write guard proves the caller holds the lock.
+    fn store(&self, write: &RwLockWriteGuard<'_, ()>, value: Option<Arc<T>>) {
+        self.store_with(write, value, Origin::Explicit);
+    }
+
+    fn store_from(&self, write: &RwLockWriteGuard<'_, ()>, value: Arc<T>, origin: Origin) {
+        self.store_with(write, Some(value), origin);
+    }
+
+    fn store_with(&self, _write: &RwLockWriteGuard<'_, ()>, value: Option<Arc<T>>, origin: Origin) {
+        #[cfg(feature = "log")]
+        match (&value, origin) {
+            (None, _) => log::debug!("GlobalMockable<{}>: cleared", std::any::type_name::<T>()),
+            (Some(_), Origin::Default) => {
+                log::debug!(
+                    "GlobalMockable<{}>: initialized",
+                    std::any::type_name::<T>()
+                )
+            }
+            (Some(_), Origin::Explicit) => {
+                log::debug!("GlobalMockable<{}>: set", std::any::type_name::<T>())
+            }
+        }
+
+        let from_default = value.is_some() && origin == Origin::Default;
+        let removed = value.is_none() && self.cell.get().is_some();
+        match &value {
+            Some(value) => self.cell.set(Arc::clone(value)),
+            None => self.cell.clear(),
+        }
+        if let Some(updates) = self.updates.get() {
+            updates.send_replace(value);
+        }
+        self.from_default.store(from_default, Ordering::Relaxed);
+        self.generation.fetch_add(1, Ordering::Relaxed);
+        if removed {
+            for cb in self.lock_clear_callbacks().iter() {
+                cb();
+            }
+        }
+    }
+
+    fn id(&self) -> usize {
+        self as *const Self as *const () as usize
+    }
+}
+
+impl<T, C> Default for GlobalMockable<T, C>
+where
+    T: ?Sized + Send + Sync,
+    C: Cell<Arc<T>> + Default,
+{
+    fn default() -> Self {
+        Self::with_cell(C::default())
+    }
+}
+
+// A restore a dropped guard could not make without stalling the runtime.
+struct DeferredRestore<T: ?Sized> {
+    value: Option<Arc<T>>,
+    origin: Origin,
+    // The instance's generation when the restore was deferred.
+    generation: u64,
+}
+
+// The write lock, making any deferred restore before it is released.
+struct WriteGuard<'a, T, C>
+where
+    T: ?Sized + Send + Sync,
+    C: Cell<Arc<T>>,
+{
+    mockable: &'a GlobalMockable<T, C>,
+    guard: RwLockWriteGuard<'a, ()>,
+}
+
+impl<'a, T, C> std::ops::Deref for WriteGuard<'a, T, C>
+where
+    T: ?Sized + Send + Sync,
+    C: Cell<Arc<T>>,
+{
+    type Target = RwLockWriteGuard<'a, ()>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<T, C> Drop for WriteGuard<'_, T, C>
+where
+    T: ?Sized + Send + Sync,
+    C: Cell<Arc<T>>,
+{
+    fn drop(&mut self) {
+        self.mockable.run_deferred(&self.guard);
+    }
+}
+
+// A share of the lock. The last reader out makes any deferred restore.
+struct ReadGuard<'a, T, C>
+where
+    T: ?Sized + Send + Sync,
+    C: Cell<Arc<T>>,
+{
+    mockable: &'a GlobalMockable<T, C>,
+    guard: Option<RwLockReadGuard<'a, ()>>,
+}
+
+impl<T, C> Drop for ReadGuard<'_, T, C>
+where
+    T: ?Sized + Send + Sync,
+    C: Cell<Arc<T>>,
+{
+    fn drop(&mut self) {
+        drop(self.guard.take());
+        if self.mockable.lock_deferred().is_none() {
+            return;
+        }
+        if let Ok(write) = self.mockable.lock.try_write() {
+            self.mockable.run_deferred(&write);
+        }
+    }
+}
+
+// Puts a value taken out of a cell back when dropped armed, so a replacement
+// that is cancelled or panics leaves the instance as it was.
+struct PutBack<'a, V, C: Cell<V>> {
+    cell: &'a C,
+    value: Option<V>,
+}
+
+impl<V, C: Cell<V>> Drop for PutBack<'_, V, C> {
+    fn drop(&mut self) {
+        if let Some(value) = self.value.take() {
+            self.cell.set(value);
+        }
+    }
+}
+
+// Exclusive right to run this global's initializer.
+struct InitPermit<'a> {
+    chain: u64,
+    _holding: init_graph::Holding,
+    _guard: MutexGuard<'a, ()>,
+}
+
+impl InitPermit<'_> {
+    async fn run<Fut: Future>(&self, init: Fut) -> Fut::Output {
+        init_graph::within_chain(self.chain, init).await
+    }
+}
+
+#[macro_export]
+macro_rules! define_global_mockable {
+    ($(#[$attr:meta])* $struct_name:ident, $trait_ty:ty, $default_impl:path) => {
+        $crate::paste::paste! {
+            // Names the problem at the macro's trait argument. The generated
+            // items only see the type through the alias, which becomes an
+            // error type when the probe fails, so they add no errors of their
+            // own.
+            $(#[$attr])*
+            #[doc(hidden)]
+            #[allow(non_upper_case_globals)]
+            const [<__ $struct_name DynCompatible>]: usize = {
+                let _: ::std::option::Option<&$trait_ty> = ::std::option::Option::None;
+                0
+            };
+
+            $(#[$attr])*
+            #[doc(hidden)]
+            type [<__ $struct_name Target>] =
+                <$crate::__private::Probed<[<__ $struct_name DynCompatible>]> as $crate::__private::Probe<$trait_ty>>::Checked;
+
+            $crate::define_global_mockable!(
+                @items $(#[$attr])* $struct_name, [<__ $struct_name Target>], $default_impl
+            );
+        }
+    };
+    (@items $(#[$attr:meta])* $struct_name:ident, $trait_ty:ty, $default_impl:path) => {
+        $(#[$attr])*
+        pub struct $struct_name;
+
+        // Not every accessor is used by every mockable.
+        $(#[$attr])*
+        #[allow(dead_code)]
+        impl $struct_name {
+            fn static_instance() -> &'static $crate::GlobalMockable<$trait_ty> {
+                static STATIC_INSTANCE: $crate::GlobalMockable<$trait_ty> =
+                    $crate::GlobalMockable::const_new();
+
+                &STATIC_INSTANCE
+            }
+
+            pub async fn get() -> ::std::sync::Arc<$trait_ty> {
+                if let Some(value) = Self::static_instance().scoped_override() {
+                    return value;
+                }
+                Self::static_instance().get_or_init(Self::default_impl).await
+            }
+
+            /// Initializes the default if needed and primes the read path,
+            /// e.g. before serving traffic.
+            pub async fn warm_up() {
+                Self::static_instance().warm_up(Self::default_impl).await;
+            }
+
+            pub fn is_initialized() -> bool {
+                Self::static_instance().is_initialized()
+            }
+
+            /// Computes something from the current value, initializing it
+            /// first if needed, without handing out an `Arc`.
+            pub async fn map<R>(f: impl FnOnce(&$trait_ty) -> R) -> R {
+                if let Some(value) = Self::static_instance().scoped_override() {
+                    return f(&*value);
+                }
+                Self::static_instance()
+                    .with_or_init(Self::default_impl, |value| f(value))
+                    .await
+            }
+
+            /// Alias of `get`, for call sites that read better as
+            /// `Service::current().await.method()`.
+            pub async fn current() -> ::std::sync::Arc<$trait_ty> {
+                Self::get().await
+            }
+
+            /// Returns the installed value, panicking instead of building the
+            /// default if there is none.
+            pub async fn current_or_panic() -> ::std::sync::Arc<$trait_ty> {
+                if let Some(value) = Self::static_instance().scoped_override() {
+                    return value;
+                }
+                Self::static_instance().current_or_panic().await
+            }
+
+            pub async fn try_current(
+            ) -> ::std::result::Result<::std::sync::Arc<$trait_ty>, $crate::GlobalMockableError> {
+                if let Some(value) = Self::static_instance().scoped_override() {
+                    return Ok(value);
+                }
+                Self::static_instance().try_current().await
+            }
+
+            pub async fn try_get() -> ::std::result::Result<::std::sync::Arc<$trait_ty>, $crate::GlobalMockableError> {
+                if let Some(value) = Self::static_instance().scoped_override() {
+                    return Ok(value);
+                }
+                Self::static_instance()
+                    .try_get_or_init(Self::default_impl)
+                    .await
+            }
+
+            async fn default_impl() -> ::std::sync::Arc<$trait_ty> {
+                match Self::static_instance().registered_default() {
+                    Some(init) => init().await,
+                    None => $default_impl().await,
+                }
+            }
+
+            /// Builds a new default instance without reading or changing the
+            /// installed value.
+            pub async fn get_fresh_default() -> ::std::sync::Arc<$trait_ty> {
+                Self::default_impl().await
+            }
+
+            pub async fn set(value: ::std::sync::Arc<$trait_ty>) {
+                Self::static_instance().set(value).await;
+            }
+
+            pub async fn set_boxed(value: ::std::boxed::Box<$trait_ty>) {
+                Self::static_instance().set_boxed(value).await;
+            }
+
+            pub async fn set_async<F, Fut>(f: F)
+            where
+                F: FnOnce() -> Fut,
+                Fut: ::std::future::Future<Output = ::std::sync::Arc<$trait_ty>>,
+            {
+                Self::static_instance().set_async(f).await;
+            }
+
+            pub async fn clear() {
+                Self::static_instance().clear().await;
+            }
+
+            pub async fn try_set(
+                value: ::std::sync::Arc<$trait_ty>,
+            ) -> ::std::result::Result<(), $crate::GlobalMockableError> {
+                Self::static_instance().try_set(value).await
+            }
+
+            pub async fn try_clear() -> ::std::result::Result<(), $crate::GlobalMockableError> {
+                Self::static_instance().try_clear().await
+            }
+
+            /// Blocking `get` for synchronous code inside a multi-thread
+            /// Tokio runtime.
+            pub fn get_blocking() -> ::std::sync::Arc<$trait_ty> {
+                if let Some(value) = Self::static_instance().scoped_override() {
+                    return value;
+                }
+                Self::static_instance().get_or_init_blocking(Self::default_impl)
+            }
+
+            pub fn set_blocking(value: ::std::sync::Arc<$trait_ty>) {
+                Self::static_instance().set_blocking(value);
+            }
+
+            pub fn clear_blocking() {
+                Self::static_instance().clear_blocking();
+            }
+
+            pub fn try_clear_sync() -> bool {
+                Self::static_instance().try_clear_sync()
+            }
+
+            pub async fn clear_and_get() -> ::std::sync::Arc<$trait_ty> {
+                Self::static_instance()
+                    .clear_and_get(Self::default_impl)
+                    .await
+            }
+
+            /// Clears the value only if it was built by the default
+            /// initializer, keeping an installed mock.
+            pub async fn clear_defaults_only() -> bool {
+                Self::static_instance().clear_if_default().await
+            }
+
+            pub async fn clear_all_including_mocks() {
+                Self::static_instance().clear_all_including_mocks().await;
+            }
+
+            pub fn set_clear_policy(policy: $crate::ClearPolicy) {
+                Self::static_instance().set_clear_policy(policy);
+            }
+
+            /// An override for [`scoped`]($crate::scoped).
+            pub fn override_with(value: ::std::sync::Arc<$trait_ty>) -> $crate::Override {
+                $crate::Override::new(Self::static_instance(), value)
+            }
+
+            pub fn set_default_boxed(f: $crate::BoxedInit<$trait_ty>) {
+                Self::static_instance().set_default_boxed(f);
+            }
+
+            /// Makes `selector`, typically choosing an implementation from
+            /// configuration, the default and initializes the global with it.
+            ///
+            /// A value installed beforehand, such as a test's mock, is kept
+            /// and the selector does not run until it is cleared. Returns
+            /// whether the selector's value was installed.
+            pub async fn seed_from<F>(selector: F) -> bool
+            where
+                F: Fn() -> ::std::sync::Arc<$trait_ty> + Send + Sync + 'static,
+            {
+                Self::set_default_boxed(::std::boxed::Box::new(move || {
+                    ::std::boxed::Box::pin(::std::future::ready(selector()))
+                }));
+                Self::static_instance()
+                    .get_or_init_reporting(Self::default_impl)
+                    .await
+                    .1
+            }
+
+            /// Installs `wrap`'s decoration of the current value, such as a
+            /// spy delegating to the default; see
+            /// [`GlobalMockable::wrap_current`]($crate::GlobalMockable::wrap_current).
+            pub async fn wrap_current<W>(wrap: W) -> ::std::sync::Arc<$trait_ty>
+            where
+                W: FnOnce(::std::sync::Arc<$trait_ty>) -> ::std::sync::Arc<$trait_ty> + Send,
+            {
+                Self::static_instance()
+                    .wrap_current(Self::default_impl, wrap)
+                    .await
+            }
+
+            /// Switches the default initializer and rebuilds the value from
+            /// it, without readers observing anything in between.
+            pub async fn replace_default_and_reinit(
+                f: $crate::BoxedInit<$trait_ty>,
+            ) -> ::std::sync::Arc<$trait_ty> {
+                Self::static_instance().replace_default_and_reinit(f).await
+            }
+
+            pub async fn freeze() {
+                Self::static_instance().freeze().await;
+            }
+        }
+
+        $crate::paste::paste! {
+            #[doc = ::std::concat!(
+                "A value handle to [`", ::std::stringify!($struct_name), "`], for storing ",
+                "the dependency in fields or capturing it in closures."
+            )]
+            $(#[$attr])*
+            #[derive(Clone, Copy, Debug, Default)]
+            pub struct [<$struct_name Ref>];
+
+            $(#[$attr])*
+            #[allow(dead_code)]
+            impl [<$struct_name Ref>] {
+                pub async fn get(&self) -> ::std::sync::Arc<$trait_ty> {
+                    $struct_name::get().await
+                }
+
+                pub async fn set(&self, value: ::std::sync::Arc<$trait_ty>) {
+                    $struct_name::set(value).await;
+                }
+
+                pub async fn clear(&self) {
+                    $struct_name::clear().await;
+                }
+            }
+        }
+
+        $(#[$attr])*
+        const _: () = {
+            $crate::inventory::submit! {
+                $crate::Registration::new(
+                    ::std::concat!(::std::module_path!(), "::", ::std::stringify!($struct_name)),
+                    || ::std::boxed::Box::pin(async {
+                        $struct_name::try_get().await.map(drop)
+                    }),
+                    || ::std::boxed::Box::pin(async {
+                        let mockable = $struct_name::static_instance();
+                        (mockable.origin().await, mockable.generation().await)
+                    }),
+                )
+            }
+        };
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct SimpleStruct {
+        value: usize,
+    }
+
+    #[tokio::test]
+    async fn supports_mocking_concrete_types() {
+        let global: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        let default_calls = Arc::new(AtomicUsize::new(0));
+
+        let first = global
+            .get_or_init({
+                let default_calls = Arc::clone(&default_calls);
+                || async move {
+                    default_calls.fetch_add(1, Ordering::SeqCst);
+                    Arc::new(SimpleStruct { value: 1 })
+                }
+            })
+            .await;
+
+        assert_eq!(first.value, 1);
+
+        global.set(Arc::new(SimpleStruct { value: 99 })).await;
+
+        let second = global
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 2 }) })
+            .await;
+
+        assert_eq!(second.value, 99);
+        assert_eq!(default_calls.load(Ordering::SeqCst), 1);
+
+        global.clear().await;
+
+        let third = global
+            .get_or_init({
+                let default_calls = Arc::clone(&default_calls);
+                || async move {
+                    default_calls.fetch_add(1, Ordering::SeqCst);
+                    Arc::new(SimpleStruct { value: 7 })
+                }
+            })
+            .await;
+
+        assert_eq!(third.value, 7);
+        assert_eq!(default_calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn cache_tracks_set_and_clear() {
+        let global: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+
+        let first = global
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 1 }) })
+            .await;
+        let cached = global
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 2 }) })
+            .await;
+        assert!(Arc::ptr_eq(&first, &cached));
+
+        let mock = Arc::new(SimpleStruct { value: 99 });
+        global.set(Arc::clone(&mock)).await;
+        let mocked = global
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 3 }) })
+            .await;
+        assert!(Arc::ptr_eq(&mock, &mocked));
+
+        global.clear().await;
+        let reset = global
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 4 }) })
+            .await;
+        assert_eq!(reset.value, 4);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_readers_observe_set() {
+        static GLOBAL: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+
+        GLOBAL
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 1 }) })
+            .await;
+
+        let readers: Vec<_> = (0..8)
+            .map(|_| {
+                tokio::spawn(async {
+                    loop {
+                        let current = GLOBAL
+                            .get_or_init(|| async { Arc::new(SimpleStruct { value: 1 }) })
+                            .await;
+                        if current.value == 99 {
+                            break;
+                        }
+                        tokio::task::yield_now().await;
+                    }
+                })
+            })
+            .collect();
+
+        GLOBAL.set(Arc::new(SimpleStruct { value: 99 })).await;
+
+        for reader in readers {
+            reader.await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn factory_can_read_another_global() {
+        static INNER: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        static OUTER: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+
+        let outer = OUTER
+            .get_or_init(|| async {
+                let inner = INNER
+                    .get_or_init(|| async { Arc::new(SimpleStruct { value: 20 }) })
+                    .await;
+                Arc::new(SimpleStruct {
+                    value: inner.value + 1,
+                })
+            })
+            .await;
+
+        assert_eq!(outer.value, 21);
+        let inner = INNER
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 0 }) })
+            .await;
+        assert_eq!(inner.value, 20);
+    }
+
+    #[tokio::test]
+    async fn set_is_not_blocked_by_running_initializer() {
+        static GLOBAL: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        let started = Arc::new(tokio::sync::Notify::new());
+        let release = Arc::new(tokio::sync::Notify::new());
+
+        let init = tokio::spawn({
+            let started = Arc::clone(&started);
+            let release = Arc::clone(&release);
+            async move {
+                GLOBAL
+                    .get_or_init(|| async move {
+                        started.notify_one();
+                        release.notified().await;
+                        Arc::new(SimpleStruct { value: 1 })
+                    })
+                    .await
+            }
+        });
+
+        started.notified().await;
+        GLOBAL.set(Arc::new(SimpleStruct { value: 99 })).await;
+        release.notify_one();
+
+        assert_eq!(init.await.unwrap().value, 99);
+        assert_eq!(GLOBAL.origin().await, Some(Origin::Explicit));
+        let current = GLOBAL
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 2 }) })
+            .await;
+        assert_eq!(current.value, 99);
+    }
+
+    #[tokio::test]
+    async fn clear_during_initialization_keeps_its_result() {
+        static GLOBAL: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        let started = Arc::new(tokio::sync::Notify::new());
+        let release = Arc::new(tokio::sync::Notify::new());
+
+        let init = tokio::spawn({
+            let started = Arc::clone(&started);
+            let release = Arc::clone(&release);
+            GLOBAL.get_or_init(|| async move {
+                started.notify_one();
+                release.notified().await;
+                Arc::new(SimpleStruct { value: 1 })
+            })
+        });
+
+        started.notified().await;
+        GLOBAL.clear().await;
+        release.notify_one();
+
+        assert_eq!(init.await.unwrap().value, 1);
+        assert_eq!(GLOBAL.origin().await, Some(Origin::Default));
+        assert_eq!(GLOBAL.clone_inner().await, Some(SimpleStruct { value: 1 }));
+    }
+
+    #[tokio::test]
+    async fn fork_is_independent_of_original() {
+        let global: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        global
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 1 }) })
+            .await;
+
+        let fork = global.fork().await;
+        let forked = fork
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 2 }) })
+            .await;
+        assert_eq!(forked.value, 1);
+
+        fork.set(Arc::new(SimpleStruct { value: 99 })).await;
+        let forked = fork
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 2 }) })
+            .await;
+        assert_eq!(forked.value, 99);
+
+        let original = global
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 3 }) })
+            .await;
+        assert_eq!(original.value, 1);
+    }
+
+    #[tokio::test]
+    async fn get_or_default_keeps_existing_value() {
+        let global: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+
+        let first = global
+            .get_or_default(Arc::new(SimpleStruct { value: 1 }))
+            .await;
+        assert_eq!(first.value, 1);
+
+        let second = global
+            .get_or_default(Arc::new(SimpleStruct { value: 2 }))
+            .await;
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn get_or_default_first_caller_wins() {
+        static GLOBAL: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+
+        let handles: Vec<_> = (0..16)
+            .map(|value| tokio::spawn(GLOBAL.get_or_default(Arc::new(SimpleStruct { value }))))
+            .collect();
+
+        let mut results = Vec::new();
+        for handle in handles {
+            results.push(handle.await.unwrap());
+        }
+        assert!(
+            results
+                .iter()
+                .all(|result| Arc::ptr_eq(result, &results[0]))
+        );
+    }
+
+    #[tokio::test]
+    async fn frozen_empty_instance_cannot_initialize() {
+        let global: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        global.freeze().await;
+
+        let result = global
+            .try_get_or_init(|| async { Arc::new(SimpleStruct { value: 1 }) })
+            .await;
+        assert_eq!(result.unwrap_err(), GlobalMockableError::Frozen);
+    }
+
+    #[tokio::test]
+    async fn frozen_instance_keeps_its_value() {
+        let global: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        global.set(Arc::new(SimpleStruct { value: 99 })).await;
+        global.freeze().await;
+
+        let value = global
+            .try_get_or_init(|| async { Arc::new(SimpleStruct { value: 1 }) })
+            .await
+            .unwrap();
+        assert_eq!(value.value, 99);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "frozen")]
+    async fn set_panics_when_frozen() {
+        let global: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        global.freeze().await;
+        global.set(Arc::new(SimpleStruct { value: 1 })).await;
+    }
+
+    #[tokio::test]
+    async fn try_get_or_init_reports_cycle() {
+        static GLOBAL: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+
+        let value = GLOBAL
+            .get_or_init(|| async {
+                let result = GLOBAL
+                    .try_get_or_init(|| async { Arc::new(SimpleStruct { value: 2 }) })
+                    .await;
+                assert_eq!(result.unwrap_err(), GlobalMockableError::Cycle);
+                Arc::new(SimpleStruct { value: 1 })
+            })
+            .await;
+        assert_eq!(value.value, 1);
+    }
+
+    #[tokio::test]
+    async fn clear_and_get_replaces_mock_with_fresh_default() {
+        let global: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        global.set(Arc::new(SimpleStruct { value: 99 })).await;
+
+        let fresh = global
+            .clear_and_get(|| async { Arc::new(SimpleStruct { value: 1 }) })
+            .await;
+        assert_eq!(fresh.value, 1);
+
+        let current = global
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 2 }) })
+            .await;
+        assert!(Arc::ptr_eq(&fresh, &current));
+    }
+
+    #[tokio::test]
+    async fn clear_and_get_hides_the_empty_window() {
+        static GLOBAL: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        GLOBAL.set(Arc::new(SimpleStruct { value: 99 })).await;
+        let started = Arc::new(tokio::sync::Notify::new());
+
+        let reset = tokio::spawn({
+            let started = Arc::clone(&started);
+            async move {
+                GLOBAL
+                    .clear_and_get(|| async move {
+                        started.notify_one();
+                        tokio::task::yield_now().await;
+                        Arc::new(SimpleStruct { value: 1 })
+                    })
+                    .await
+            }
+        });
+
+        started.notified().await;
+        let observed = GLOBAL
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 2 }) })
+            .await;
+        assert_eq!(observed.value, 1);
+        assert_eq!(reset.await.unwrap().value, 1);
+    }
+
+    #[tokio::test]
+    async fn downgraded_value_dies_on_clear() {
+        let global: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        assert!(global.downgrade().await.is_none());
+
+        global.set(Arc::new(SimpleStruct { value: 1 })).await;
+        let weak = global.downgrade().await.unwrap();
+        assert_eq!(weak.upgrade().unwrap().value, 1);
+
+        global.clear().await;
+        assert!(weak.upgrade().is_none());
+    }
+
+    #[tokio::test]
+    async fn swap_with_computes_from_previous_value() {
+        let global: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+
+        let initial = global
+            .swap_with(|previous| async move {
+                assert!(previous.is_none());
+                Arc::new(SimpleStruct { value: 1 })
+            })
+            .await;
+        assert_eq!(initial.value, 1);
+
+        let next = global
+            .swap_with(|previous| async move {
+                Arc::new(SimpleStruct {
+                    value: previous.unwrap().value + 10,
+                })
+            })
+            .await;
+        assert_eq!(next.value, 11);
+
+        let current = global
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 0 }) })
+            .await;
+        assert!(Arc::ptr_eq(&next, &current));
+    }
+
+    #[tokio::test]
+    async fn cancelled_initialization_caches_nothing() {
+        let global: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        let started = tokio::sync::Notify::new();
+
+        tokio::select! {
+            _ = global.get_or_init(|| async {
+                started.notify_one();
+                std::future::pending::<()>().await;
+                unreachable!()
+            }) => unreachable!(),
+            _ = started.notified() => {}
+        }
+
+        let calls = AtomicUsize::new(0);
+        for _ in 0..3 {
+            let value = global
+                .get_or_init(|| async {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    Arc::new(SimpleStruct { value: 2 })
+                })
+                .await;
+            assert_eq!(value.value, 2);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn replace_if_respects_predicate() {
+        let global: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+
+        let replaced = global
+            .replace_if(
+                |current| current.is_none(),
+                Arc::new(SimpleStruct { value: 1 }),
+            )
+            .await;
+        assert!(replaced);
+
+        global.set(Arc::new(SimpleStruct { value: 99 })).await;
+        let replaced = global
+            .replace_if(
+                |current| current.is_none_or(|current| current.value != 99),
+                Arc::new(SimpleStruct { value: 2 }),
+            )
+            .await;
+        assert!(!replaced);
+
+        let current = global
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 0 }) })
+            .await;
+        assert_eq!(current.value, 99);
+    }
+
+    #[tokio::test]
+    async fn default_matches_const_new() {
+        let global = GlobalMockable::<SimpleStruct>::default();
+        assert!(global.downgrade().await.is_none());
+
+        let value = global
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 1 }) })
+            .await;
+        assert_eq!(value.value, 1);
+
+        global.set(Arc::new(SimpleStruct { value: 99 })).await;
+        let value = global
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 2 }) })
+            .await;
+        assert_eq!(value.value, 99);
+    }
+
+    #[tokio::test]
+    async fn swap_guard_restores_explicitly() {
+        let global: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        global.set(Arc::new(SimpleStruct { value: 1 })).await;
+
+        let guard = global.swap(Arc::new(SimpleStruct { value: 99 })).await;
+        let swapped = global
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 0 }) })
+            .await;
+        assert_eq!(swapped.value, 99);
+
+        guard.restore().await;
+        let restored = global
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 0 }) })
+            .await;
+        assert_eq!(restored.value, 1);
+    }
+
+    #[tokio::test]
+    async fn swap_guard_restores_origin() {
+        let global: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        global
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 1 }) })
+            .await;
+
+        let guard = global.swap(Arc::new(SimpleStruct { value: 99 })).await;
+        guard.restore().await;
+        assert_eq!(global.origin().await, Some(Origin::Default));
+        assert!(global.clear_if_default().await);
+    }
+
+    #[tokio::test]
+    async fn swap_guard_drop_restores_origin() {
+        let global: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        global
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 1 }) })
+            .await;
+
+        drop(global.swap(Arc::new(SimpleStruct { value: 99 })).await);
+        assert_eq!(global.origin().await, Some(Origin::Default));
+        assert!(global.clear_if_default().await);
+    }
+
+    #[tokio::test]
+    async fn swap_guard_restores_on_drop() {
+        let global: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+
+        let guard = global.swap(Arc::new(SimpleStruct { value: 99 })).await;
+        drop(guard);
+
+        assert!(global.downgrade().await.is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn swap_guard_drop_waits_for_contended_lock() {
+        static GLOBAL: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        GLOBAL.set(Arc::new(SimpleStruct { value: 1 })).await;
+
+        let guard = GLOBAL.swap(Arc::new(SimpleStruct { value: 99 })).await;
+        let write = GLOBAL.lock.write().await;
+        let dropper = tokio::spawn(async move { drop(guard) });
+        tokio::task::yield_now().await;
+        drop(write);
+        dropper.await.unwrap();
+
+        let restored = GLOBAL
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 0 }) })
+            .await;
+        assert_eq!(restored.value, 1);
+    }
+
+    #[tokio::test]
+    async fn swap_guard_drop_defers_to_lock_holder() {
+        let global: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        global.set(Arc::new(SimpleStruct { value: 1 })).await;
+
+        // Blocking would stall the holder, so it restores when it lets go.
+        let guard = global.swap(Arc::new(SimpleStruct { value: 99 })).await;
+        let write = global.write().await;
+        drop(guard);
+        assert_eq!(
+            global.cached().as_deref(),
+            Some(&SimpleStruct { value: 99 })
+        );
+        drop(write);
+        assert_eq!(global.cached().as_deref(), Some(&SimpleStruct { value: 1 }));
+
+        // A reader restores as well.
+        let guard = global.swap(Arc::new(SimpleStruct { value: 99 })).await;
+        let read = global.read().await;
+        drop(guard);
+        drop(read);
+        assert_eq!(global.cached().as_deref(), Some(&SimpleStruct { value: 1 }));
+
+        // A write made after the drop wins.
+        let guard = global.swap(Arc::new(SimpleStruct { value: 99 })).await;
+        let write = global.write().await;
+        drop(guard);
+        global.store(&write, Some(Arc::new(SimpleStruct { value: 2 })));
+        drop(write);
+        assert_eq!(global.cached().as_deref(), Some(&SimpleStruct { value: 2 }));
+    }
+
+    #[tokio::test]
+    async fn set_scoped_restores_when_guard_drops() {
+        static GLOBAL: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        GLOBAL.set(Arc::new(SimpleStruct { value: 1 })).await;
+
+        let guard = GLOBAL
+            .set_scoped(Arc::new(SimpleStruct { value: 99 }))
+            .await;
+        assert_eq!(GLOBAL.clone_inner().await, Some(SimpleStruct { value: 99 }));
+        drop(guard);
+        assert_eq!(GLOBAL.clone_inner().await, Some(SimpleStruct { value: 1 }));
+
+        // With the lock busy, the restore runs on the runtime afterwards.
+        let guard = GLOBAL
+            .set_scoped(Arc::new(SimpleStruct { value: 99 }))
+            .await;
+        let write = GLOBAL.lock.write().await;
+        drop(guard);
+        drop(write);
+        tokio::task::yield_now().await;
+        assert_eq!(GLOBAL.clone_inner().await, Some(SimpleStruct { value: 1 }));
+    }
+
+    #[tokio::test]
+    async fn set_scoped_restore_yields_to_later_set() {
+        static GLOBAL: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        GLOBAL.set(Arc::new(SimpleStruct { value: 1 })).await;
+
+        let guard = GLOBAL
+            .set_scoped(Arc::new(SimpleStruct { value: 99 }))
+            .await;
+        let write = GLOBAL.lock.write().await;
+        drop(guard);
+        drop(write);
+        GLOBAL.set(Arc::new(SimpleStruct { value: 2 })).await;
+        tokio::task::yield_now().await;
+        assert_eq!(GLOBAL.clone_inner().await, Some(SimpleStruct { value: 2 }));
+    }
+
+    #[tokio::test]
+    async fn set_scoped_restores_origin() {
+        static GLOBAL: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        GLOBAL
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 1 }) })
+            .await;
+
+        drop(
+            GLOBAL
+                .set_scoped(Arc::new(SimpleStruct { value: 99 }))
+                .await,
+        );
+        assert_eq!(GLOBAL.origin().await, Some(Origin::Default));
+
+        // The restore spawned while the lock is busy keeps the origin too.
+        let guard = GLOBAL
+            .set_scoped(Arc::new(SimpleStruct { value: 99 }))
+            .await;
+        let write = GLOBAL.lock.write().await;
+        drop(guard);
+        drop(write);
+        tokio::task::yield_now().await;
+        assert_eq!(GLOBAL.clone_inner().await, Some(SimpleStruct { value: 1 }));
+        assert!(GLOBAL.clear_if_default().await);
+    }
+
+    #[test]
+    fn set_scoped_guard_outlives_runtime() {
+        static GLOBAL: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let guard = runtime.block_on(GLOBAL.set_scoped(Arc::new(SimpleStruct { value: 99 })));
+        drop(runtime);
+
+        // An uncontended restore needs no runtime.
+        drop(guard);
+        assert!(!GLOBAL.is_initialized());
+
+        // A contended one is spawned onto the dead runtime, which drops it.
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let guard = runtime.block_on(GLOBAL.set_scoped(Arc::new(SimpleStruct { value: 99 })));
+        drop(runtime);
+        let write = GLOBAL.lock.try_write().unwrap();
+        drop(guard);
+        drop(write);
+        assert_eq!(
+            GLOBAL.cached().as_deref(),
+            Some(&SimpleStruct { value: 99 })
+        );
+    }
+
+    #[tokio::test]
+    async fn cancelled_swap_with_keeps_previous_value() {
+        let global: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        global.set(Arc::new(SimpleStruct { value: 1 })).await;
+        let mut updates = global.subscribe().await;
+        updates.borrow_and_update();
+
+        let swap = global.swap_with(|_| std::future::pending());
+        let timed_out = tokio::time::timeout(std::time::Duration::from_millis(10), swap).await;
+        assert!(timed_out.is_err());
+
+        assert_eq!(global.clone_inner().await, Some(SimpleStruct { value: 1 }));
+        assert_eq!(global.generation().await, 1);
+        assert!(!updates.has_changed().unwrap());
+    }
+
+    #[tokio::test]
+    async fn panicking_swap_with_keeps_previous_value() {
+        static GLOBAL: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        GLOBAL.set(Arc::new(SimpleStruct { value: 1 })).await;
+
+        let swap = tokio::spawn(GLOBAL.swap_with(|_| async { panic!("replacement failed") }));
+        assert!(swap.await.unwrap_err().is_panic());
+
+        assert_eq!(GLOBAL.clone_inner().await, Some(SimpleStruct { value: 1 }));
+        assert_eq!(GLOBAL.origin().await, Some(Origin::Explicit));
+    }
+
+    #[tokio::test]
+    async fn initializer_may_borrow_from_caller() {
+        let global: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        let setup = String::from("abc");
+
+        let value = global
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: setup.len() }) })
+            .await;
+        assert_eq!(value.value, 3);
+        assert_eq!(setup, "abc");
+    }
+
+    #[tokio::test]
+    async fn subscribers_see_changes() {
+        let global: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        let mut updates = global.subscribe().await;
+        assert!(updates.borrow_and_update().is_none());
+
+        global
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 1 }) })
+            .await;
+        assert!(updates.has_changed().unwrap());
+        assert_eq!(updates.borrow_and_update().as_ref().unwrap().value, 1);
+
+        global.clear().await;
+        assert!(updates.has_changed().unwrap());
+        assert!(updates.borrow_and_update().is_none());
+    }
+
+    #[tokio::test]
+    async fn set_if_changed_skips_equal_values() {
+        let global: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        global.set(Arc::new(SimpleStruct { value: 1 })).await;
+        let mut updates = global.subscribe().await;
+        updates.borrow_and_update();
+
+        assert!(
+            !global
+                .set_if_changed(Arc::new(SimpleStruct { value: 1 }))
+                .await
+        );
+        assert!(!updates.has_changed().unwrap());
+
+        assert!(
+            global
+                .set_if_changed(Arc::new(SimpleStruct { value: 2 }))
+                .await
+        );
+        assert!(updates.has_changed().unwrap());
+        assert_eq!(updates.borrow_and_update().as_ref().unwrap().value, 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn cold_start_stampede_runs_initializer_once() {
+        static GLOBAL: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        let handles: Vec<_> = (0..100)
+            .map(|_| {
+                tokio::spawn(GLOBAL.get_or_init(|| async {
+                    CALLS.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                    Arc::new(SimpleStruct { value: 1 })
+                }))
+            })
+            .collect();
+
+        for handle in handles {
+            assert_eq!(handle.await.unwrap().value, 1);
+        }
+        assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_failed_initialization_caches_nothing() {
+        static GLOBAL: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        static FAILING: AtomicBool = AtomicBool::new(true);
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        async fn init() -> Result<Arc<SimpleStruct>, &'static str> {
+            CALLS.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            if FAILING.load(Ordering::SeqCst) {
+                Err("unavailable")
+            } else {
+                Ok(Arc::new(SimpleStruct { value: 1 }))
+            }
+        }
+
+        let stampede = || {
+            (0..8)
+                .map(|_| tokio::spawn(GLOBAL.get_or_try_init(init)))
+                .collect::<Vec<_>>()
+        };
+
+        for handle in stampede() {
+            assert_eq!(handle.await.unwrap(), Err("unavailable"));
+        }
+        // Every waiter ran its own attempt rather than sharing one.
+        assert_eq!(CALLS.load(Ordering::SeqCst), 8);
+        assert_eq!(GLOBAL.origin().await, None);
+
+        FAILING.store(false, Ordering::SeqCst);
+        let mut results = Vec::new();
+        for handle in stampede() {
+            results.push(handle.await.unwrap().unwrap());
+        }
+        assert_eq!(CALLS.load(Ordering::SeqCst), 9);
+        for value in &results {
+            assert!(Arc::ptr_eq(value, &results[0]));
+        }
+        assert_eq!(GLOBAL.origin().await, Some(Origin::Default));
+    }
+
+    #[tokio::test]
+    async fn get_or_init_reporting_flags_only_the_initializing_call() {
+        static GLOBAL: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+
+        let handles: Vec<_> = (0..10)
+            .map(|_| {
+                tokio::spawn(GLOBAL.get_or_init_reporting(|| async {
+                    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                    Arc::new(SimpleStruct { value: 1 })
+                }))
+            })
+            .collect();
+        let mut initialized = 0;
+        for handle in handles {
+            let (value, fresh) = handle.await.unwrap();
+            assert_eq!(value.value, 1);
+            initialized += usize::from(fresh);
+        }
+        assert_eq!(initialized, 1);
+
+        let (_, fresh) = GLOBAL
+            .get_or_init_reporting(|| async { Arc::new(SimpleStruct { value: 2 }) })
+            .await;
+        assert!(!fresh);
+    }
+
+    #[tokio::test]
+    async fn get_or_init_future_polls_only_when_empty() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        let polled = AtomicUsize::new(0);
+        let init = |value| {
+            let polled = &polled;
+            async move {
+                polled.fetch_add(1, Ordering::SeqCst);
+                Arc::new(SimpleStruct { value })
+            }
+        };
+
+        assert_eq!(mockable.get_or_init_future(init(1)).await.value, 1);
+        assert_eq!(mockable.get_or_init_future(init(2)).await.value, 1);
+        assert_eq!(polled.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn clone_inner_is_detached() {
+        let global: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        assert_eq!(global.clone_inner().await, None);
+
+        global.set(Arc::new(SimpleStruct { value: 1 })).await;
+        let mut owned = global.clone_inner().await.unwrap();
+        owned.value += 1;
+
+        global.set(Arc::new(SimpleStruct { value: 99 })).await;
+        assert_eq!(owned.value, 2);
+        assert_eq!(global.clone_inner().await.unwrap().value, 99);
+    }
+
+    #[tokio::test]
+    async fn clear_if_default_keeps_explicit_values() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        assert!(!mockable.clear_if_default().await);
+
+        mockable
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 1 }) })
+            .await;
+        assert_eq!(mockable.origin().await, Some(Origin::Default));
+        assert!(mockable.clear_if_default().await);
+        assert_eq!(mockable.origin().await, None);
+
+        mockable.set(Arc::new(SimpleStruct { value: 2 })).await;
+        assert_eq!(mockable.origin().await, Some(Origin::Explicit));
+        assert!(!mockable.clear_if_default().await);
+        assert_eq!(
+            mockable.clone_inner().await,
+            Some(SimpleStruct { value: 2 })
+        );
+    }
+
+    #[tokio::test]
+    async fn generation_counts_mutations_only() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        let init = || async { Arc::new(SimpleStruct { value: 1 }) };
+        assert_eq!(mockable.generation().await, 0);
+
+        mockable.get_or_init(init).await;
+        mockable.get_or_init(init).await;
+        assert_eq!(mockable.generation().await, 1);
+
+        mockable.set(Arc::new(SimpleStruct { value: 2 })).await;
+        assert_eq!(mockable.generation().await, 2);
+        mockable.clear().await;
+        assert_eq!(mockable.generation().await, 3);
+        mockable.get_or_init(init).await;
+        assert_eq!(mockable.generation().await, 4);
+    }
+
+    #[tokio::test]
+    async fn get_or_init_sync_runs_closure_once() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        let calls = AtomicUsize::new(0);
+        let init = || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Arc::new(SimpleStruct { value: 1 })
+        };
+
+        let first = mockable.get_or_init_sync(init).await;
+        let second = mockable.get_or_init_sync(init).await;
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[cfg(feature = "async-closures")]
+    #[tokio::test]
+    async fn get_or_init_async_accepts_async_closure() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        let value = 7;
+
+        let first = mockable
+            .get_or_init_async(async || Arc::new(SimpleStruct { value }))
+            .await;
+        assert_eq!(first.value, 7);
+        let second = mockable
+            .get_or_init_async(async || Arc::new(SimpleStruct { value: 8 }))
+            .await;
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+
+    #[cfg(feature = "log")]
+    mod logging {
+        use super::*;
+        use std::sync::Mutex;
+
+        struct Capture;
+
+        static MESSAGES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+        impl log::Log for Capture {
+            fn enabled(&self, _: &log::Metadata) -> bool {
+                true
+            }
+
+            fn log(&self, record: &log::Record) {
+                MESSAGES.lock().unwrap().push(record.args().to_string());
+            }
+
+            fn flush(&self) {}
+        }
+
+        struct Logged;
+
+        #[tokio::test]
+        async fn logs_init_set_and_clear() {
+            log::set_logger(&Capture).unwrap();
+            log::set_max_level(log::LevelFilter::Debug);
+
+            let mockable = GlobalMockable::<Logged>::const_new();
+            mockable.get_or_init(|| async { Arc::new(Logged) }).await;
+            mockable.set(Arc::new(Logged)).await;
+            mockable.clear().await;
+
+            // Other tests log too; only `Logged` matters here.
+            let messages: Vec<_> = MESSAGES
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|message| message.contains("Logged>"))
+                .cloned()
+                .collect();
+            let name = std::any::type_name::<Logged>();
+            assert_eq!(
+                messages,
+                [
+                    format!("GlobalMockable<{name}>: initialized"),
+                    format!("GlobalMockable<{name}>: set"),
+                    format!("GlobalMockable<{name}>: cleared"),
+                ]
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn shared_arc_instance_is_seen_by_every_task() {
+        let shared = GlobalMockable::<SimpleStruct>::new_arc();
+
+        let writer = Arc::clone(&shared);
+        tokio::spawn(async move { writer.set(Arc::new(SimpleStruct { value: 5 })).await })
+            .await
+            .unwrap();
+
+        let reader = Arc::clone(&shared);
+        let seen = tokio::spawn(async move { reader.clone_inner().await })
+            .await
+            .unwrap();
+        assert_eq!(seen, Some(SimpleStruct { value: 5 }));
+    }
+
+    #[tokio::test]
+    async fn clear_callbacks_fire_on_clear_only() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        let clears = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&clears);
+        mockable
+            .on_clear(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+            })
+            .await;
+
+        mockable.set(Arc::new(SimpleStruct { value: 1 })).await;
+        mockable.set(Arc::new(SimpleStruct { value: 2 })).await;
+        assert_eq!(clears.load(Ordering::SeqCst), 0);
+
+        mockable.clear().await;
+        assert_eq!(clears.load(Ordering::SeqCst), 1);
+        // Nothing left to remove.
+        mockable.clear().await;
+        assert_eq!(clears.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn try_clear_sync_gives_up_on_contention() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        mockable.set(Arc::new(SimpleStruct { value: 1 })).await;
+
+        let held = mockable.lock.write().await;
+        assert!(!mockable.try_clear_sync());
+        drop(held);
+        assert_eq!(
+            mockable.clone_inner().await,
+            Some(SimpleStruct { value: 1 })
+        );
+
+        assert!(mockable.try_clear_sync());
+        assert_eq!(mockable.clone_inner().await, None);
+    }
+
+    #[tokio::test]
+    async fn set_async_installs_after_constructor_finishes() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        mockable.set(Arc::new(SimpleStruct { value: 1 })).await;
+
+        let install = mockable.set_async(|| async {
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            Arc::new(SimpleStruct { value: 2 })
+        });
+        let read_meanwhile = async {
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+            mockable.clone_inner().await
+        };
+        let ((), seen) = tokio::join!(install, read_meanwhile);
+
+        assert_eq!(seen, Some(SimpleStruct { value: 1 }));
+        assert_eq!(
+            mockable.clone_inner().await,
+            Some(SimpleStruct { value: 2 })
+        );
+    }
+
+    #[tokio::test]
+    async fn try_variants_report_errors() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        assert_eq!(
+            mockable.try_current().await,
+            Err(GlobalMockableError::Uninitialized)
+        );
+
+        mockable.set(Arc::new(SimpleStruct { value: 1 })).await;
+        mockable.freeze().await;
+        assert_eq!(
+            mockable.try_set(Arc::new(SimpleStruct { value: 2 })).await,
+            Err(GlobalMockableError::Frozen)
+        );
+        assert_eq!(mockable.try_clear().await, Err(GlobalMockableError::Frozen));
+        assert_eq!(
+            mockable.try_current().await.as_deref(),
+            Ok(&SimpleStruct { value: 1 })
+        );
+    }
+
+    #[tokio::test]
+    async fn try_get_or_try_init_reports_frozen() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        mockable.freeze().await;
+        let result = mockable
+            .try_get_or_try_init(|| async { Ok::<_, &str>(Arc::new(SimpleStruct { value: 1 })) })
+            .await;
+        assert_eq!(
+            result.unwrap_err(),
+            TryInitError::Mockable(GlobalMockableError::Frozen)
+        );
+    }
+
+    #[tokio::test]
+    async fn try_set_if_changed_reports_frozen() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        mockable.freeze().await;
+        assert_eq!(
+            mockable
+                .try_set_if_changed(Arc::new(SimpleStruct { value: 1 }))
+                .await,
+            Err(GlobalMockableError::Frozen)
+        );
+    }
+
+    #[tokio::test]
+    async fn try_replace_arc_reports_frozen() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        mockable.freeze().await;
+        assert_eq!(
+            mockable
+                .try_replace_arc(Arc::new(SimpleStruct { value: 1 }))
+                .await,
+            Err(GlobalMockableError::Frozen)
+        );
+    }
+
+    #[tokio::test]
+    async fn try_swap_reports_frozen() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        mockable.freeze().await;
+        let result = mockable.try_swap(Arc::new(SimpleStruct { value: 1 })).await;
+        assert!(matches!(result, Err(GlobalMockableError::Frozen)));
+    }
+
+    #[tokio::test]
+    async fn try_set_scoped_reports_frozen() {
+        static GLOBAL: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        GLOBAL.freeze().await;
+        let result = GLOBAL
+            .try_set_scoped(Arc::new(SimpleStruct { value: 1 }))
+            .await;
+        assert!(matches!(result, Err(GlobalMockableError::Frozen)));
+    }
+
+    #[tokio::test]
+    async fn try_replace_if_reports_frozen() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        mockable.freeze().await;
+        assert_eq!(
+            mockable
+                .try_replace_if(|_| true, Arc::new(SimpleStruct { value: 1 }))
+                .await,
+            Err(GlobalMockableError::Frozen)
+        );
+    }
+
+    #[tokio::test]
+    async fn try_clear_all_including_mocks_reports_frozen() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        mockable.freeze().await;
+        assert_eq!(
+            mockable.try_clear_all_including_mocks().await,
+            Err(GlobalMockableError::Frozen)
+        );
     }
 
-    pub async fn get_or_init<F, Fut>(&self, f: F) -> Arc<T>
-    where
-        F: FnOnce() -> Fut,
-        Fut: Future<Output = Arc<T>> + Send,
-    {
-        self.instance
-            .read()
-            .await
-            .get_or_init::<F, Fut>(f)
-            .await
-            .clone()
+    #[tokio::test]
+    async fn try_clear_if_default_reports_frozen() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        mockable
+            .get_or_init(|| async { Arc::new(SimpleStruct { value: 1 }) })
+            .await;
+        mockable.freeze().await;
+        assert_eq!(
+            mockable.try_clear_if_default().await,
+            Err(GlobalMockableError::Frozen)
+        );
     }
 
-    pub async fn set(&self, value: Arc<T>) {
-        let mut write = self.instance.write().await;
-        *write = OnceCell::const_new_with(value);
+    #[tokio::test]
+    async fn try_clear_and_get_reports_frozen() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        mockable.freeze().await;
+        let result = mockable
+            .try_clear_and_get(|| async { Arc::new(SimpleStruct { value: 1 }) })
+            .await;
+        assert_eq!(result, Err(GlobalMockableError::Frozen));
     }
 
-    pub async fn clear(&self) {
-        let mut write = self.instance.write().await;
-        *write = OnceCell::const_new();
+    #[tokio::test]
+    async fn try_swap_with_reports_cycle() {
+        static GLOBAL: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+
+        let value = GLOBAL
+            .swap_with(|_| async {
+                let result = GLOBAL
+                    .try_swap_with(|_| async { Arc::new(SimpleStruct { value: 2 }) })
+                    .await;
+                assert_eq!(result, Err(GlobalMockableError::Cycle));
+                Arc::new(SimpleStruct { value: 1 })
+            })
+            .await;
+        assert_eq!(value.value, 1);
     }
-}
 
-#[macro_export]
-macro_rules! define_global_mockable {
-    ($struct_name:ident, $trait_ty:ty, $default_impl:path) => {
-        pub struct $struct_name;
+    #[tokio::test]
+    async fn try_wrap_current_reports_frozen() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        mockable.freeze().await;
+        let result = mockable
+            .try_wrap_current(
+                || async { Arc::new(SimpleStruct { value: 1 }) },
+                |current| current,
+            )
+            .await;
+        assert_eq!(result, Err(GlobalMockableError::Frozen));
+    }
 
-        impl $struct_name {
-            fn static_instance() -> &'static $crate::GlobalMockable<$trait_ty> {
-                static STATIC_INSTANCE: $crate::GlobalMockable<$trait_ty> =
-                    $crate::GlobalMockable::const_new();
+    #[tokio::test]
+    async fn try_replace_default_and_reinit_reports_frozen() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        mockable.freeze().await;
+        let result = mockable
+            .try_replace_default_and_reinit(Box::new(|| {
+                Box::pin(async { Arc::new(SimpleStruct { value: 1 }) })
+            }))
+            .await;
+        assert_eq!(result, Err(GlobalMockableError::Frozen));
+        assert!(mockable.registered_default().is_none());
+    }
 
-                &STATIC_INSTANCE
-            }
+    #[test]
+    fn try_get_or_init_blocking_needs_a_runtime() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        let result =
+            mockable.try_get_or_init_blocking(|| async { Arc::new(SimpleStruct { value: 1 }) });
+        assert_eq!(result, Err(GlobalMockableError::NoRuntime));
+    }
 
-            pub async fn get() -> ::std::sync::Arc<$trait_ty> {
-                Self::static_instance().get_or_init(Self::default_impl).await
-            }
+    #[tokio::test]
+    async fn try_blocking_variants_need_multi_thread_runtime() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        assert_eq!(
+            mockable.try_set_blocking(Arc::new(SimpleStruct { value: 1 })),
+            Err(GlobalMockableError::NoRuntime)
+        );
+        assert_eq!(
+            mockable.try_clear_blocking(),
+            Err(GlobalMockableError::NoRuntime)
+        );
+    }
 
-            async fn default_impl() -> ::std::sync::Arc<$trait_ty> {
-                $default_impl().await
-            }
+    #[test]
+    fn try_blocking_variants_need_a_runtime() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        assert_eq!(
+            mockable.try_clear_blocking(),
+            Err(GlobalMockableError::NoRuntime)
+        );
+    }
 
-            pub async fn set(value: ::std::sync::Arc<$trait_ty>) {
-                Self::static_instance().set(value).await;
-            }
+    #[tokio::test]
+    async fn warm_up_initializes_ahead_of_first_get() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        let inits = AtomicUsize::new(0);
+        let init = || async {
+            inits.fetch_add(1, Ordering::SeqCst);
+            Arc::new(SimpleStruct { value: 1 })
+        };
+        assert!(!mockable.is_initialized());
 
-            pub async fn clear() {
-                Self::static_instance().clear().await;
-            }
-        }
-    };
-}
+        mockable.warm_up(init).await;
+        assert!(mockable.is_initialized());
+        assert_eq!(inits.load(Ordering::SeqCst), 1);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::sync::atomic::{AtomicUsize, Ordering};
+        assert_eq!(mockable.get_or_init(init).await.value, 1);
+        assert_eq!(inits.load(Ordering::SeqCst), 1);
+    }
 
-    #[derive(Debug)]
-    struct SimpleStruct {
-        value: usize,
+    #[tokio::test]
+    async fn get_weak_or_init_does_not_keep_value_alive() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        let weak = mockable
+            .get_weak_or_init(|| async { Arc::new(SimpleStruct { value: 1 }) })
+            .await;
+        assert_eq!(weak.upgrade().as_deref(), Some(&SimpleStruct { value: 1 }));
+
+        mockable.clear().await;
+        assert!(weak.upgrade().is_none());
     }
 
     #[tokio::test]
-    async fn supports_mocking_concrete_types() {
-        let global: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
-        let default_calls = Arc::new(AtomicUsize::new(0));
+    async fn replace_arc_returns_previous_value() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        let mut updates = mockable.subscribe().await;
+        assert!(
+            mockable
+                .replace_arc(Arc::new(SimpleStruct { value: 1 }))
+                .await
+                .is_none()
+        );
 
-        let first = global
-            .get_or_init({
-                let default_calls = Arc::clone(&default_calls);
-                || async move {
-                    default_calls.fetch_add(1, Ordering::SeqCst);
-                    Arc::new(SimpleStruct { value: 1 })
-                }
-            })
+        let first = mockable.get_or_init(|| async { unreachable!() }).await;
+        let previous = mockable
+            .replace_arc(Arc::new(SimpleStruct { value: 2 }))
+            .await
+            .unwrap();
+        assert!(Arc::ptr_eq(&previous, &first));
+        assert_eq!(
+            mockable.clone_inner().await,
+            Some(SimpleStruct { value: 2 })
+        );
+        assert_eq!(mockable.origin().await, Some(Origin::Explicit));
+        assert_eq!(mockable.generation().await, 2);
+
+        updates.changed().await.unwrap();
+        assert_eq!(
+            updates.borrow_and_update().as_deref(),
+            Some(&SimpleStruct { value: 2 })
+        );
+    }
+
+    #[tokio::test]
+    async fn subscribe_current_observes_following_update() {
+        let mockable = GlobalMockable::<SimpleStruct>::const_new();
+        let (current, updates) = mockable
+            .subscribe_current(|| async { Arc::new(SimpleStruct { value: 1 }) })
             .await;
+        assert_eq!(current.value, 1);
 
-        assert_eq!(first.value, 1);
+        mockable.set(Arc::new(SimpleStruct { value: 2 })).await;
+        let mut updates = std::pin::pin!(updates);
+        assert_eq!(updates.next().await.unwrap().value, 2);
 
-        global.set(Arc::new(SimpleStruct { value: 99 })).await;
+        mockable.clear().await;
+        mockable.set(Arc::new(SimpleStruct { value: 3 })).await;
+        assert_eq!(updates.next().await.unwrap().value, 3);
+    }
 
-        let second = global
-            .get_or_init(|| async { Arc::new(SimpleStruct { value: 2 }) })
-            .await;
+    type BoxedInit = std::pin::Pin<Box<dyn Future<Output = Arc<SimpleStruct>> + Send>>;
 
-        assert_eq!(second.value, 99);
-        assert_eq!(default_calls.load(Ordering::SeqCst), 1);
+    static CYCLE_A: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+    static CYCLE_B: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
 
-        global.clear().await;
+    fn cycle_a() -> BoxedInit {
+        Box::pin(CYCLE_B.get_or_init(cycle_b))
+    }
 
-        let third = global
-            .get_or_init({
-                let default_calls = Arc::clone(&default_calls);
-                || async move {
-                    default_calls.fetch_add(1, Ordering::SeqCst);
-                    Arc::new(SimpleStruct { value: 7 })
-                }
+    fn cycle_b() -> BoxedInit {
+        Box::pin(CYCLE_A.get_or_init(cycle_a))
+    }
+
+    #[tokio::test]
+    async fn detects_cycle_between_globals() {
+        let result = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            tokio::spawn(async { CYCLE_A.get_or_init(cycle_a).await }),
+        )
+        .await
+        .expect("cyclic initialization deadlocked");
+
+        let panic = result.unwrap_err().into_panic();
+        let message = panic.downcast_ref::<String>().unwrap();
+        assert!(message.contains("cyclic initialization"), "{message}");
+
+        // The failed attempt releases both init locks.
+        CYCLE_B.set(Arc::new(SimpleStruct { value: 2 })).await;
+        let a = CYCLE_A.get_or_init(cycle_a).await;
+        assert_eq!(a.value, 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn detects_cycle_across_concurrent_tasks() {
+        static LEFT: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        static RIGHT: GlobalMockable<SimpleStruct> = GlobalMockable::const_new();
+        static ARRIVED: AtomicUsize = AtomicUsize::new(0);
+
+        async fn both_initializing() {
+            ARRIVED.fetch_add(1, Ordering::SeqCst);
+            while ARRIVED.load(Ordering::SeqCst) < 2 {
+                tokio::task::yield_now().await;
+            }
+        }
+
+        fn left() -> BoxedInit {
+            Box::pin(async {
+                both_initializing().await;
+                RIGHT.get_or_init(right).await
             })
-            .await;
+        }
 
-        assert_eq!(third.value, 7);
-        assert_eq!(default_calls.load(Ordering::SeqCst), 2);
+        fn right() -> BoxedInit {
+            Box::pin(async {
+                both_initializing().await;
+                LEFT.get_or_init(left).await
+            })
+        }
+
+        let first = tokio::spawn(LEFT.get_or_init(left));
+        let second = tokio::spawn(RIGHT.get_or_init(right));
+
+        let (first, second) = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            (first.await, second.await)
+        })
+        .await
+        .expect("cyclic initialization deadlocked");
+
+        assert!(first.unwrap_err().is_panic());
+        assert!(second.unwrap_err().is_panic());
     }
 
     pub trait Greeter: Send + Sync {
@@ -171,4 +3135,361 @@ mod tests {
         let reset = TestGreeter::get().await;
         assert_eq!(reset.greet(), "hello");
     }
+
+    define_global_mockable!(AliasGreeter, dyn Greeter, default_greeter);
+
+    #[tokio::test]
+    async fn current_is_an_alias_of_get() {
+        assert_eq!(AliasGreeter::current().await.greet(), "hello");
+
+        AliasGreeter::set(Arc::new(MockGreeter)).await;
+        assert_eq!(AliasGreeter::current().await.greet(), "mock hello");
+        assert!(Arc::ptr_eq(
+            &AliasGreeter::current().await,
+            &AliasGreeter::get().await
+        ));
+    }
+
+    define_global_mockable!(BlockingGreeter, dyn Greeter, default_greeter);
+
+    // A synchronous fixture, as test frameworks without async setup call them.
+    fn install_mock_greeter() {
+        BlockingGreeter::set_blocking(Arc::new(MockGreeter));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn blocking_set_and_clear_from_sync_code() {
+        install_mock_greeter();
+        assert_eq!(BlockingGreeter::get().await.greet(), "mock hello");
+
+        BlockingGreeter::clear_blocking();
+        assert_eq!(BlockingGreeter::get_blocking().greet(), "hello");
+    }
+
+    #[test]
+    #[should_panic(expected = "GlobalMockable::get requires a Tokio runtime; none is running")]
+    fn blocking_get_without_runtime_panics() {
+        BlockingGreeter::get_blocking();
+    }
+
+    define_global_mockable!(HandleGreeter, dyn Greeter, default_greeter);
+
+    struct Welcome {
+        greeter: HandleGreeterRef,
+    }
+
+    #[tokio::test]
+    async fn ref_handle_delegates_to_the_global() {
+        let welcome = Welcome {
+            greeter: HandleGreeterRef,
+        };
+        assert_eq!(welcome.greeter.get().await.greet(), "hello");
+
+        HandleGreeter::set(Arc::new(MockGreeter)).await;
+        let greeter = welcome.greeter;
+        let greet = || async move { greeter.get().await.greet() };
+        assert_eq!(greet().await, "mock hello");
+
+        welcome.greeter.clear().await;
+        assert_eq!(HandleGreeter::get().await.greet(), "hello");
+    }
+
+    define_global_mockable!(ScopedGreeter, dyn Greeter, default_greeter);
+    define_global_mockable!(ScopedRepo, dyn Repo, default_repo);
+
+    #[tokio::test]
+    async fn scoped_overrides_several_mockables() {
+        let overrides = vec![
+            ScopedGreeter::override_with(Arc::new(MockGreeter)),
+            ScopedRepo::override_with(Arc::new(MockRepo)),
+        ];
+        scoped(overrides, async {
+            assert_eq!(ScopedGreeter::get().await.greet(), "mock hello");
+            assert_eq!(ScopedRepo::get().await.name(), "mock");
+
+            // Other tasks keep seeing the installed values.
+            let outside = tokio::spawn(ScopedGreeter::get()).await.unwrap();
+            assert_eq!(outside.greet(), "hello");
+        })
+        .await;
+
+        assert_eq!(ScopedGreeter::get().await.greet(), "hello");
+        assert_eq!(ScopedRepo::get().await.name(), "real");
+    }
+
+    define_global_mockable!(WiredGreeter, dyn Greeter, default_greeter);
+
+    #[tokio::test]
+    async fn current_or_panic_returns_installed_value() {
+        WiredGreeter::set(Arc::new(MockGreeter)).await;
+        assert_eq!(WiredGreeter::current_or_panic().await.greet(), "mock hello");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "was never initialized; call set() during startup")]
+    async fn current_or_panic_never_builds_default() {
+        GlobalMockable::<SimpleStruct>::const_new()
+            .current_or_panic()
+            .await;
+    }
+
+    define_global_mockable!(MappedGreeter, dyn Greeter, default_greeter);
+
+    #[tokio::test]
+    async fn map_derives_from_current_value() {
+        assert_eq!(MappedGreeter::map(|g| g.greet().to_string()).await, "hello");
+
+        MappedGreeter::set(Arc::new(MockGreeter)).await;
+        assert_eq!(MappedGreeter::map(|g| g.greet().len()).await, 10);
+    }
+
+    define_global_mockable!(BoxedMockGreeter, dyn Greeter, default_greeter);
+
+    #[tokio::test]
+    async fn set_boxed_installs_boxed_mock() {
+        let mock: Box<dyn Greeter> = Box::new(MockGreeter);
+        BoxedMockGreeter::set_boxed(mock).await;
+        assert_eq!(BoxedMockGreeter::get().await.greet(), "mock hello");
+    }
+
+    struct SpyGreeter {
+        inner: Arc<dyn Greeter>,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl Greeter for SpyGreeter {
+        fn greet(&self) -> &'static str {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.inner.greet()
+        }
+    }
+
+    define_global_mockable!(SpiedGreeter, dyn Greeter, default_greeter);
+
+    #[tokio::test]
+    async fn wrap_current_installs_delegating_spy() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        SpiedGreeter::wrap_current(|inner| {
+            Arc::new(SpyGreeter {
+                inner,
+                calls: counter,
+            })
+        })
+        .await;
+
+        assert_eq!(SpiedGreeter::get().await.greet(), "hello");
+        assert_eq!(SpiedGreeter::get().await.greet(), "hello");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        SpiedGreeter::clear().await;
+        SpiedGreeter::get().await.greet();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    struct PluginGreeter;
+
+    impl Greeter for PluginGreeter {
+        fn greet(&self) -> &'static str {
+            "plugin hello"
+        }
+    }
+
+    define_global_mockable!(HotSwapGreeter, dyn Greeter, default_greeter);
+
+    #[tokio::test]
+    async fn replace_default_and_reinit_switches_baseline() {
+        assert_eq!(HotSwapGreeter::get().await.greet(), "hello");
+
+        let plugin = HotSwapGreeter::replace_default_and_reinit(Box::new(|| {
+            Box::pin(async { Arc::new(PluginGreeter) as Arc<dyn Greeter> })
+        }))
+        .await;
+        assert_eq!(plugin.greet(), "plugin hello");
+        assert!(Arc::ptr_eq(&HotSwapGreeter::get().await, &plugin));
+
+        HotSwapGreeter::clear().await;
+        assert_eq!(HotSwapGreeter::get().await.greet(), "plugin hello");
+    }
+
+    fn select_greeter(backend: &str) -> Arc<dyn Greeter> {
+        match backend {
+            "plugin" => Arc::new(PluginGreeter),
+            _ => Arc::new(DefaultGreeter),
+        }
+    }
+
+    define_global_mockable!(PluginSeededGreeter, dyn Greeter, default_greeter);
+    define_global_mockable!(MockSeededGreeter, dyn Greeter, default_greeter);
+
+    #[tokio::test]
+    async fn seed_from_installs_selected_implementation() {
+        static SELECTIONS: AtomicUsize = AtomicUsize::new(0);
+
+        let seeded = PluginSeededGreeter::seed_from(|| {
+            SELECTIONS.fetch_add(1, Ordering::SeqCst);
+            select_greeter("plugin")
+        })
+        .await;
+        assert!(seeded);
+        assert_eq!(PluginSeededGreeter::get().await.greet(), "plugin hello");
+        assert_eq!(PluginSeededGreeter::get().await.greet(), "plugin hello");
+        assert_eq!(SELECTIONS.load(Ordering::SeqCst), 1);
+
+        // Clearing a mock falls back to the selection, not the static default.
+        PluginSeededGreeter::set(Arc::new(MockGreeter)).await;
+        PluginSeededGreeter::clear().await;
+        assert_eq!(PluginSeededGreeter::get().await.greet(), "plugin hello");
+    }
+
+    #[tokio::test]
+    async fn seed_from_keeps_installed_mock() {
+        MockSeededGreeter::set(Arc::new(MockGreeter)).await;
+
+        assert!(!MockSeededGreeter::seed_from(|| select_greeter("default")).await);
+        assert_eq!(MockSeededGreeter::get().await.greet(), "mock hello");
+
+        MockSeededGreeter::clear().await;
+        assert_eq!(MockSeededGreeter::get().await.greet(), "hello");
+    }
+
+    define_global_mockable!(BoxedDefaultGreeter, dyn Greeter, default_greeter);
+
+    #[tokio::test]
+    async fn uses_boxed_default_when_registered() {
+        BoxedDefaultGreeter::set_default_boxed(Box::new(|| {
+            Box::pin(async { Arc::new(PluginGreeter) as Arc<dyn Greeter> })
+        }));
+
+        let plugin = BoxedDefaultGreeter::get().await;
+        assert_eq!(plugin.greet(), "plugin hello");
+
+        BoxedDefaultGreeter::set(Arc::new(MockGreeter)).await;
+        assert_eq!(BoxedDefaultGreeter::get().await.greet(), "mock hello");
+
+        BoxedDefaultGreeter::clear().await;
+        assert_eq!(BoxedDefaultGreeter::get().await.greet(), "plugin hello");
+    }
+
+    struct TestOnlyGreeter;
+
+    impl Greeter for TestOnlyGreeter {
+        fn greet(&self) -> &'static str {
+            "test only hello"
+        }
+    }
+
+    async fn test_only_greeter() -> Arc<dyn Greeter> {
+        Arc::new(TestOnlyGreeter)
+    }
+
+    // Both definitions share a name, so this only compiles if the `cfg`s are
+    // applied to everything the macro emits.
+    define_global_mockable!(
+        #[cfg(test)]
+        CfgGreeter,
+        dyn Greeter,
+        test_only_greeter
+    );
+    define_global_mockable!(
+        #[cfg(not(test))]
+        CfgGreeter,
+        dyn Greeter,
+        default_greeter
+    );
+
+    #[tokio::test]
+    async fn forwards_cfg_attributes() {
+        assert_eq!(CfgGreeter::get().await.greet(), "test only hello");
+    }
+
+    static FRESH_DEFAULT_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    async fn counted_greeter() -> Arc<dyn Greeter> {
+        FRESH_DEFAULT_CALLS.fetch_add(1, Ordering::SeqCst);
+        Arc::new(DefaultGreeter)
+    }
+
+    define_global_mockable!(FreshGreeter, dyn Greeter, counted_greeter);
+
+    #[tokio::test]
+    async fn fresh_default_leaves_mock_installed() {
+        FreshGreeter::set(Arc::new(MockGreeter)).await;
+
+        let fresh = FreshGreeter::get_fresh_default().await;
+        assert_eq!(fresh.greet(), "hello");
+        assert_eq!(FRESH_DEFAULT_CALLS.load(Ordering::SeqCst), 1);
+
+        assert_eq!(FreshGreeter::get().await.greet(), "mock hello");
+        assert_eq!(FRESH_DEFAULT_CALLS.load(Ordering::SeqCst), 1);
+    }
+
+    define_global_mockable!(StickyGreeter, dyn Greeter, default_greeter);
+
+    #[tokio::test]
+    async fn mocks_survive_defaults_only_clears() {
+        StickyGreeter::get().await;
+        assert!(StickyGreeter::clear_defaults_only().await);
+
+        StickyGreeter::set(Arc::new(MockGreeter)).await;
+        assert!(!StickyGreeter::clear_defaults_only().await);
+        assert_eq!(StickyGreeter::get().await.greet(), "mock hello");
+
+        StickyGreeter::set_clear_policy(ClearPolicy::DefaultsOnly);
+        StickyGreeter::clear().await;
+        assert_eq!(StickyGreeter::get().await.greet(), "mock hello");
+
+        StickyGreeter::clear_all_including_mocks().await;
+        assert_eq!(StickyGreeter::get().await.greet(), "hello");
+    }
+
+    pub trait Repo: Send + Sync {
+        fn name(&self) -> String;
+    }
+
+    struct RealRepo;
+
+    impl Repo for RealRepo {
+        fn name(&self) -> String {
+            "real".to_string()
+        }
+    }
+
+    struct MockRepo;
+
+    impl Repo for MockRepo {
+        fn name(&self) -> String {
+            "mock".to_string()
+        }
+    }
+
+    struct AuditedRepo(Arc<dyn Repo>);
+
+    impl Repo for AuditedRepo {
+        fn name(&self) -> String {
+            format!("audited {}", self.0.name())
+        }
+    }
+
+    async fn default_repo() -> Arc<dyn Repo> {
+        Arc::new(RealRepo)
+    }
+
+    async fn default_audited_repo() -> Arc<dyn Repo> {
+        Arc::new(AuditedRepo(InnerRepo::get().await))
+    }
+
+    define_global_mockable!(InnerRepo, dyn Repo, default_repo);
+    define_global_mockable!(OuterRepo, dyn Repo, default_audited_repo);
+
+    #[tokio::test]
+    async fn layered_default_wraps_current_inner_value() {
+        InnerRepo::clear().await;
+        OuterRepo::clear().await;
+        assert_eq!(OuterRepo::get().await.name(), "audited real");
+
+        InnerRepo::set(Arc::new(MockRepo)).await;
+        OuterRepo::clear().await;
+        assert_eq!(OuterRepo::get().await.name(), "audited mock");
+    }
 }
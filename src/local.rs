@@ -0,0 +1,116 @@
+use std::cell::RefCell;
+use std::future::Future;
+use std::rc::Rc;
+
+/// A single-threaded counterpart of [`GlobalMockable`](crate::GlobalMockable)
+/// for values that are neither `Send` nor `Sync`, such as mocks built on `Rc`
+/// or `RefCell`.
+///
+/// It is meant for tests on a current-thread runtime, with the tasks sharing
+/// it running on a [`LocalSet`](tokio::task::LocalSet). Being `!Sync`, it
+/// cannot be a `static`; share it through an `Rc`, or leak one into a
+/// `thread_local!`.
+///
+/// The methods mirror `GlobalMockable`'s, minus its cross-task guarantees: a
+/// `set` while `get_or_init`'s initializer runs still wins, but concurrent
+/// initializers on an empty instance each run, the first to finish installing
+/// its value.
+pub struct LocalGlobalMockable<T>
+where
+    T: ?Sized,
+{
+    instance: RefCell<Option<Rc<T>>>,
+}
+
+impl<T> LocalGlobalMockable<T>
+where
+    T: ?Sized,
+{
+    pub const fn const_new() -> Self {
+        LocalGlobalMockable {
+            instance: RefCell::new(None),
+        }
+    }
+
+    /// Returns the current value, running `f` to initialize it if empty.
+    pub async fn get_or_init<F, Fut>(&self, f: F) -> Rc<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Rc<T>>,
+    {
+        if let Some(current) = self.current() {
+            return current;
+        }
+        // No borrow is held while `f` runs, so it may use this instance.
+        let value = f().await;
+        Rc::clone(self.instance.borrow_mut().get_or_insert(value))
+    }
+
+    pub async fn set(&self, value: Rc<T>) {
+        *self.instance.borrow_mut() = Some(value);
+    }
+
+    pub async fn clear(&self) {
+        self.instance.borrow_mut().take();
+    }
+
+    fn current(&self) -> Option<Rc<T>> {
+        self.instance.borrow().clone()
+    }
+}
+
+impl<T> Default for LocalGlobalMockable<T>
+where
+    T: ?Sized,
+{
+    fn default() -> Self {
+        Self::const_new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use tokio::task::LocalSet;
+
+    trait Counter {
+        fn bump(&self) -> u32;
+    }
+
+    // Holds an `Rc`, so it is neither `Send` nor `Sync`.
+    struct SharedCounter(Rc<Cell<u32>>);
+
+    impl Counter for SharedCounter {
+        fn bump(&self) -> u32 {
+            self.0.set(self.0.get() + 1);
+            self.0.get()
+        }
+    }
+
+    async fn default_counter() -> Rc<dyn Counter> {
+        Rc::new(SharedCounter(Rc::new(Cell::new(100))))
+    }
+
+    #[tokio::test]
+    async fn installs_rc_mock_on_local_set() {
+        let mockable = Rc::new(LocalGlobalMockable::<dyn Counter>::const_new());
+        let observed = Rc::new(Cell::new(0));
+
+        LocalSet::new()
+            .run_until(async {
+                let installer = Rc::clone(&mockable);
+                let mock = Rc::new(SharedCounter(Rc::clone(&observed)));
+                tokio::task::spawn_local(async move { installer.set(mock).await })
+                    .await
+                    .unwrap();
+
+                assert_eq!(mockable.get_or_init(default_counter).await.bump(), 1);
+                assert_eq!(observed.get(), 1);
+
+                mockable.clear().await;
+                assert_eq!(mockable.get_or_init(default_counter).await.bump(), 101);
+            })
+            .await;
+    }
+}
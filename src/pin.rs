@@ -0,0 +1,83 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use crate::GlobalMockable;
+
+/// A [`GlobalMockable`] whose values are pinned, for services that must not
+/// move once constructed.
+pub struct PinGlobalMockable<T>
+where
+    T: ?Sized + Send + Sync,
+{
+    inner: GlobalMockable<Pin<Arc<T>>>,
+}
+
+impl<T> PinGlobalMockable<T>
+where
+    T: ?Sized + Send + Sync,
+{
+    pub const fn const_new() -> Self {
+        PinGlobalMockable {
+            inner: GlobalMockable::const_new(),
+        }
+    }
+
+    pub async fn get_or_init<F, Fut>(&self, f: F) -> Pin<Arc<T>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Pin<Arc<T>>> + Send,
+    {
+        let value = self
+            .inner
+            .get_or_init(|| {
+                let init = f();
+                async move { Arc::new(init.await) }
+            })
+            .await;
+        Pin::clone(&value)
+    }
+
+    pub async fn set(&self, value: Pin<Arc<T>>) {
+        self.inner.set(Arc::new(value)).await;
+    }
+
+    pub async fn clear(&self) {
+        self.inner.clear().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::marker::PhantomPinned;
+
+    struct Pinned {
+        value: usize,
+        _pinned: PhantomPinned,
+    }
+
+    fn pinned(value: usize) -> Pin<Arc<Pinned>> {
+        Arc::pin(Pinned {
+            value,
+            _pinned: PhantomPinned,
+        })
+    }
+
+    #[tokio::test]
+    async fn swaps_pinned_values() {
+        let global: PinGlobalMockable<Pinned> = PinGlobalMockable::const_new();
+
+        let first = global.get_or_init(|| async { pinned(1) }).await;
+        assert_eq!(first.value, 1);
+
+        let mock = pinned(99);
+        global.set(Pin::clone(&mock)).await;
+        let mocked = global.get_or_init(|| async { pinned(2) }).await;
+        assert!(std::ptr::eq(&*mocked, &*mock));
+
+        global.clear().await;
+        let reset = global.get_or_init(|| async { pinned(3) }).await;
+        assert_eq!(reset.value, 3);
+    }
+}
@@ -0,0 +1,85 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::{GlobalMockableError, Origin};
+
+type InitFuture = Pin<Box<dyn Future<Output = Result<(), GlobalMockableError>> + Send>>;
+type StateFuture = Pin<Box<dyn Future<Output = (Option<Origin>, u64)> + Send>>;
+
+/// A mockable defined with [`define_global_mockable!`](crate::define_global_mockable),
+/// collected at link time so the whole set can be driven at once.
+pub struct Registration {
+    name: &'static str,
+    init: fn() -> InitFuture,
+    state: fn() -> StateFuture,
+}
+
+impl Registration {
+    #[doc(hidden)]
+    pub const fn new(
+        name: &'static str,
+        init: fn() -> InitFuture,
+        state: fn() -> StateFuture,
+    ) -> Self {
+        Registration { name, init, state }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+inventory::collect!(Registration);
+
+/// Initializes every registered mockable that is still empty with its
+/// default, so start-up fails fast instead of on first use.
+///
+/// All mockables are attempted; the ones that could not be initialized are
+/// returned by name.
+pub async fn init_all() -> Result<(), Vec<(&'static str, GlobalMockableError)>> {
+    let mut errors = Vec::new();
+    for registration in inventory::iter::<Registration> {
+        if let Err(err) = (registration.init)().await {
+            errors.push((registration.name, err));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Whether a mockable holds a value, and where it came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockableState {
+    Empty,
+    Initialized(Origin),
+}
+
+/// The state of one registered mockable, as reported by [`snapshot_all`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockableSnapshot {
+    pub name: &'static str,
+    pub state: MockableState,
+    /// See [`GlobalMockable::generation`](crate::GlobalMockable::generation).
+    pub generation: u64,
+}
+
+/// Reports the state of every registered mockable, e.g. for a debug endpoint.
+///
+/// Each entry is read on its own, so the snapshot is not atomic across
+/// mockables that change while it is taken.
+pub async fn snapshot_all() -> Vec<MockableSnapshot> {
+    let mut snapshots = Vec::new();
+    for registration in inventory::iter::<Registration> {
+        let (origin, generation) = (registration.state)().await;
+        snapshots.push(MockableSnapshot {
+            name: registration.name,
+            state: origin.map_or(MockableState::Empty, MockableState::Initialized),
+            generation,
+        });
+    }
+    snapshots
+}
@@ -0,0 +1,142 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::{Cell, GlobalMockable, TryInitError};
+
+/// How often [`get_or_init_retry`](GlobalMockable::get_or_init_retry)
+/// attempts a fallible initialization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first. Zero is treated as one.
+    pub max_attempts: u32,
+    /// Pause between a failed attempt and the next.
+    pub delay: Duration,
+}
+
+impl RetryPolicy {
+    pub const fn new(max_attempts: u32, delay: Duration) -> Self {
+        RetryPolicy {
+            max_attempts,
+            delay,
+        }
+    }
+}
+
+impl<T, C> GlobalMockable<T, C>
+where
+    T: ?Sized + Send + Sync,
+    C: Cell<Arc<T>>,
+{
+    /// Like [`get_or_try_init`](Self::get_or_try_init), retrying a failed
+    /// initialization according to `policy`. The instance stays empty between
+    /// attempts, and the last attempt's error is returned if all fail.
+    ///
+    /// # Panics
+    ///
+    /// Panics in the cases where
+    /// [`try_get_or_init_retry`](Self::try_get_or_init_retry) returns
+    /// [`TryInitError::Mockable`].
+    pub async fn get_or_init_retry<F, Fut, E>(&self, policy: RetryPolicy, f: F) -> Result<Arc<T>, E>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<Arc<T>, E>> + Send,
+    {
+        self.try_get_or_init_retry(policy, f)
+            .await
+            .map_err(|err| match err {
+                TryInitError::Init(err) => err,
+                TryInitError::Mockable(err) => self.fail(err),
+            })
+    }
+
+    /// Like [`get_or_init_retry`](Self::get_or_init_retry), returning the
+    /// states in which no attempt can run as [`TryInitError::Mockable`]
+    /// instead of panicking. Those are not retried.
+    pub async fn try_get_or_init_retry<F, Fut, E>(
+        &self,
+        policy: RetryPolicy,
+        mut f: F,
+    ) -> Result<Arc<T>, TryInitError<E>>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<Arc<T>, E>> + Send,
+    {
+        let mut attempt = 1;
+        loop {
+            match self.try_get_or_try_init(&mut f).await {
+                Err(TryInitError::Init(_)) if attempt < policy.max_attempts => {
+                    attempt += 1;
+                    tokio::time::sleep(policy.delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GlobalMockableError;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn retries_until_initializer_succeeds() {
+        let global: GlobalMockable<usize> = GlobalMockable::const_new();
+        let attempts = AtomicUsize::new(0);
+        let policy = RetryPolicy::new(5, Duration::from_millis(1));
+
+        let value = global
+            .get_or_init_retry(policy, || async {
+                match attempts.fetch_add(1, Ordering::SeqCst) {
+                    0 | 1 => Err("flaky"),
+                    _ => Ok(Arc::new(7)),
+                }
+            })
+            .await;
+        assert_eq!(*value.unwrap(), 7);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+        let cached = global
+            .get_or_init_retry(policy, || async { Err("not called") })
+            .await;
+        assert_eq!(*cached.unwrap(), 7);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_attempts() {
+        let global: GlobalMockable<usize> = GlobalMockable::const_new();
+        let attempts = AtomicUsize::new(0);
+
+        let result = global
+            .get_or_init_retry(RetryPolicy::new(2, Duration::ZERO), || async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err::<Arc<usize>, _>("down")
+            })
+            .await;
+        assert_eq!(result.unwrap_err(), "down");
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert!(global.downgrade().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn try_variant_reports_frozen_without_retrying() {
+        let global: GlobalMockable<usize> = GlobalMockable::const_new();
+        global.freeze().await;
+        let attempts = AtomicUsize::new(0);
+
+        let result = global
+            .try_get_or_init_retry(RetryPolicy::new(3, Duration::ZERO), || async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Ok::<_, &str>(Arc::new(1))
+            })
+            .await;
+        assert_eq!(
+            result.unwrap_err(),
+            TryInitError::Mockable(GlobalMockableError::Frozen)
+        );
+        assert_eq!(attempts.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,71 @@
+use std::any::Any;
+use std::future::Future;
+use std::sync::Arc;
+
+use crate::{Cell, GlobalMockable};
+
+tokio::task_local! {
+    static OVERRIDES: Vec<Override>;
+}
+
+/// A value to install for the duration of a [`scoped`] block.
+#[derive(Clone)]
+pub struct Override {
+    mockable: usize,
+    // An `Arc<T>` for the overridden `GlobalMockable<T, _>`.
+    value: Arc<dyn Any + Send + Sync>,
+}
+
+impl Override {
+    pub fn new<T, C>(mockable: &'static GlobalMockable<T, C>, value: Arc<T>) -> Self
+    where
+        T: ?Sized + Send + Sync + 'static,
+        C: Cell<Arc<T>>,
+    {
+        Override {
+            mockable: mockable.id(),
+            value: Arc::new(value),
+        }
+    }
+}
+
+/// Runs `fut` with every mockable in `overrides` resolving to its override,
+/// without touching the installed values.
+///
+/// The overrides are task-local: other tasks, including ones `fut` spawns,
+/// keep seeing the installed values, so concurrent tests cannot observe each
+/// other's mocks. Everything reverts once `fut` completes. Nested blocks see
+/// the overrides of the blocks around them, with the innermost winning.
+///
+/// Overrides are honored by the [`define_global_mockable!`](crate::define_global_mockable)
+/// accessors; code using a [`GlobalMockable`] directly can consult
+/// [`scoped_override`](GlobalMockable::scoped_override).
+pub async fn scoped<Fut: Future>(overrides: Vec<Override>, fut: Fut) -> Fut::Output {
+    let mut all = OVERRIDES.try_with(Vec::clone).unwrap_or_default();
+    all.extend(overrides);
+    OVERRIDES.scope(all, fut).await
+}
+
+impl<T, C> GlobalMockable<T, C>
+where
+    T: ?Sized + Send + Sync + 'static,
+    C: Cell<Arc<T>>,
+{
+    /// The value overriding this instance in the current task's [`scoped`]
+    /// block, if any.
+    #[must_use]
+    pub fn scoped_override(&self) -> Option<Arc<T>> {
+        let id = self.id();
+        OVERRIDES
+            .try_with(|overrides| {
+                overrides
+                    .iter()
+                    .rev()
+                    .find(|entry| entry.mockable == id)
+                    .and_then(|entry| entry.value.downcast_ref::<Arc<T>>())
+                    .map(Arc::clone)
+            })
+            .ok()
+            .flatten()
+    }
+}
@@ -0,0 +1,114 @@
+//! The synchronization primitives [`GlobalMockable`](crate::GlobalMockable)
+//! is built on.
+//!
+//! Under `--cfg loom` they are replaced by equivalents backed by loom's
+//! primitives, so `tests/loom.rs` can model-check the real implementation.
+//! Loom objects must be created inside a model, so those are created on first
+//! use, which keeps every constructor `const`.
+
+#[cfg(not(loom))]
+pub(crate) use arc_swap::ArcSwapOption;
+#[cfg(not(loom))]
+pub(crate) use tokio::sync::{Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+#[cfg(loom)]
+pub(crate) use self::model::{
+    ArcSwapOption, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard,
+};
+
+#[cfg(loom)]
+mod model {
+    use std::sync::{Arc, OnceLock};
+
+    pub(crate) use loom::sync::{RwLockReadGuard, RwLockWriteGuard};
+
+    // Holds the initial value until the loom object is first needed.
+    struct Lazy<T, L> {
+        initial: std::sync::Mutex<Option<T>>,
+        inner: OnceLock<L>,
+    }
+
+    impl<T, L> Lazy<T, L> {
+        const fn new(initial: T) -> Self {
+            Lazy {
+                initial: std::sync::Mutex::new(Some(initial)),
+                inner: OnceLock::new(),
+            }
+        }
+
+        fn get(&self, create: impl FnOnce(T) -> L) -> &L {
+            self.inner.get_or_init(|| {
+                let initial = self.initial.lock().unwrap().take();
+                create(initial.expect("initial value already taken"))
+            })
+        }
+    }
+
+    pub(crate) struct RwLock<T>(Lazy<T, loom::sync::RwLock<T>>);
+
+    impl<T> RwLock<T> {
+        pub(crate) const fn const_new(value: T) -> Self {
+            RwLock(Lazy::new(value))
+        }
+
+        fn inner(&self) -> &loom::sync::RwLock<T> {
+            self.0.get(loom::sync::RwLock::new)
+        }
+
+        pub(crate) async fn read(&self) -> RwLockReadGuard<'_, T> {
+            self.inner().read().unwrap()
+        }
+
+        pub(crate) async fn write(&self) -> RwLockWriteGuard<'_, T> {
+            self.inner().write().unwrap()
+        }
+
+        pub(crate) fn try_write(&self) -> Result<RwLockWriteGuard<'_, T>, ()> {
+            self.inner().try_write().map_err(drop)
+        }
+
+        pub(crate) fn blocking_write(&self) -> RwLockWriteGuard<'_, T> {
+            self.inner().write().unwrap()
+        }
+    }
+
+    // Only ever held as a token across awaits, so it needs no `Deref`.
+    pub(crate) struct MutexGuard<'a, T>(#[allow(dead_code)] loom::sync::MutexGuard<'a, T>);
+
+    // SAFETY: loom runs every modelled thread on the same OS thread, so the
+    // guard never actually leaves the thread that locked it. This lets the
+    // boxed `Send` futures built on `GlobalMockable` compile under loom.
+    unsafe impl<T> Send for MutexGuard<'_, T> {}
+
+    pub(crate) struct Mutex<T>(Lazy<T, loom::sync::Mutex<T>>);
+
+    impl<T> Mutex<T> {
+        pub(crate) const fn const_new(value: T) -> Self {
+            Mutex(Lazy::new(value))
+        }
+
+        pub(crate) async fn lock(&self) -> MutexGuard<'_, T> {
+            MutexGuard(self.0.get(loom::sync::Mutex::new).lock().unwrap())
+        }
+    }
+
+    pub(crate) struct ArcSwapOption<T>(Lazy<(), loom::sync::RwLock<Option<Arc<T>>>>);
+
+    impl<T> ArcSwapOption<T> {
+        pub(crate) const fn const_empty() -> Self {
+            ArcSwapOption(Lazy::new(()))
+        }
+
+        fn inner(&self) -> &loom::sync::RwLock<Option<Arc<T>>> {
+            self.0.get(|()| loom::sync::RwLock::new(None))
+        }
+
+        pub(crate) fn load(&self) -> Option<Arc<T>> {
+            self.inner().read().unwrap().clone()
+        }
+
+        pub(crate) fn swap(&self, value: Option<Arc<T>>) -> Option<Arc<T>> {
+            std::mem::replace(&mut *self.inner().write().unwrap(), value)
+        }
+    }
+}
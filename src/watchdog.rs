@@ -0,0 +1,246 @@
+//! Diagnostics for lock acquisitions that take suspiciously long, enabled by
+//! the `lock-watchdog` feature.
+//!
+//! A test that deadlocks on a [`GlobalMockable`](crate::GlobalMockable) lock
+//! otherwise just hangs. With the watchdog, any acquisition waiting longer
+//! than the [threshold](set_threshold) is reported to the
+//! [handler](set_handler) while it keeps waiting. Waits are timed on a
+//! background thread rather than Tokio's time driver, so this works on any
+//! runtime.
+
+use std::backtrace::Backtrace;
+use std::future::{Future, poll_fn};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Condvar, Mutex, MutexGuard, Once, RwLock};
+use std::task::Poll;
+use std::time::{Duration, Instant};
+
+static THRESHOLD_NANOS: AtomicU64 = AtomicU64::new(5_000_000_000);
+static HANDLER: RwLock<fn(&SlowLock)> = RwLock::new(report);
+
+static WAITING: Mutex<Vec<Waiting>> = Mutex::new(Vec::new());
+static WAKE: Condvar = Condvar::new();
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+static START: Once = Once::new();
+
+/// A lock acquisition that exceeded the threshold.
+#[derive(Debug)]
+pub struct SlowLock {
+    /// The `T` of the `GlobalMockable<T>` whose lock is contended.
+    pub type_name: &'static str,
+    /// Which lock: `"read"`, `"write"` or `"init"`.
+    pub lock: &'static str,
+    /// How long the acquisition had waited when it was reported.
+    pub waited: Duration,
+    /// Where the waiting call came from; only captured when enabled through
+    /// `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE`.
+    pub backtrace: Backtrace,
+}
+
+/// Sets how long an acquisition may wait before it is reported. Defaults to
+/// five seconds.
+pub fn set_threshold(threshold: Duration) {
+    let nanos = u64::try_from(threshold.as_nanos()).unwrap_or(u64::MAX);
+    THRESHOLD_NANOS.store(nanos, Ordering::Relaxed);
+    WAKE.notify_all();
+}
+
+/// Replaces the reporter of slow acquisitions. By default they are logged as
+/// warnings through `log` with the `log` feature, and printed to stderr
+/// without it.
+pub fn set_handler(handler: fn(&SlowLock)) {
+    *HANDLER
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = handler;
+}
+
+fn report(slow: &SlowLock) {
+    let message = format_args!(
+        "GlobalMockable<{}> has waited {:?} for its {} lock; a writer or initializer is likely \
+         stuck\n{}",
+        slow.type_name, slow.waited, slow.lock, slow.backtrace
+    );
+    #[cfg(feature = "log")]
+    log::warn!("{message}");
+    #[cfg(not(feature = "log"))]
+    eprintln!("warning: {message}");
+}
+
+fn threshold() -> Duration {
+    Duration::from_nanos(THRESHOLD_NANOS.load(Ordering::Relaxed))
+}
+
+// An acquisition that did not succeed on its first poll.
+struct Waiting {
+    id: u64,
+    type_name: &'static str,
+    lock: &'static str,
+    started: Instant,
+    // Taken once the acquisition has been reported.
+    backtrace: Option<Backtrace>,
+}
+
+fn waiting() -> MutexGuard<'static, Vec<Waiting>> {
+    WAITING
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+// Keeps an acquisition on the watch list until it completes or is dropped.
+struct Tracked(u64);
+
+impl Tracked {
+    fn new(type_name: &'static str, lock: &'static str) -> Self {
+        START.call_once(|| {
+            std::thread::Builder::new()
+                .name("global-mockable-watchdog".to_owned())
+                .spawn(run)
+                .expect("failed to spawn the lock watchdog thread");
+        });
+
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        waiting().push(Waiting {
+            id,
+            type_name,
+            lock,
+            started: Instant::now(),
+            backtrace: Some(Backtrace::capture()),
+        });
+        WAKE.notify_all();
+        Tracked(id)
+    }
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        waiting().retain(|waiting| waiting.id != self.0);
+    }
+}
+
+// Reports each tracked acquisition once it has waited past the threshold.
+fn run() {
+    let mut waiting = waiting();
+    loop {
+        let now = Instant::now();
+        let threshold = threshold();
+        let mut due = Vec::new();
+        let mut next_deadline = None::<Instant>;
+        for entry in waiting.iter_mut() {
+            if entry.backtrace.is_none() {
+                continue;
+            }
+            let deadline = entry.started + threshold;
+            if deadline <= now {
+                due.push(SlowLock {
+                    type_name: entry.type_name,
+                    lock: entry.lock,
+                    waited: now - entry.started,
+                    backtrace: entry.backtrace.take().expect("checked above"),
+                });
+            } else {
+                next_deadline = Some(next_deadline.map_or(deadline, |next| next.min(deadline)));
+            }
+        }
+
+        if !due.is_empty() {
+            drop(waiting);
+            let handler = *HANDLER
+                .read()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            for slow in &due {
+                handler(slow);
+            }
+            waiting = self::waiting();
+            continue;
+        }
+
+        waiting = match next_deadline {
+            Some(deadline) => {
+                WAKE.wait_timeout(waiting, deadline - now)
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .0
+            }
+            None => WAKE
+                .wait(waiting)
+                .unwrap_or_else(|poisoned| poisoned.into_inner()),
+        };
+    }
+}
+
+pub(crate) async fn watch<Fut: Future>(
+    type_name: &'static str,
+    lock: &'static str,
+    acquire: Fut,
+) -> Fut::Output {
+    let mut acquire = std::pin::pin!(acquire);
+    // Uncontended acquisitions complete on the first poll and are never
+    // tracked.
+    let mut tracked = None;
+    poll_fn(|cx| match acquire.as_mut().poll(cx) {
+        Poll::Ready(acquired) => Poll::Ready(acquired),
+        Poll::Pending => {
+            tracked.get_or_insert_with(|| Tracked::new(type_name, lock));
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GlobalMockable;
+    use std::sync::{Arc, Mutex};
+
+    struct Watched;
+    struct Untimed;
+
+    static REPORTS: Mutex<Vec<(&'static str, &'static str)>> = Mutex::new(Vec::new());
+
+    // Other tests share the handler, so each test only counts its own type.
+    fn record(slow: &SlowLock) {
+        REPORTS.lock().unwrap().push((slow.type_name, slow.lock));
+    }
+
+    fn reports_for(name: &str) -> Vec<&'static str> {
+        let reports = REPORTS.lock().unwrap();
+        reports
+            .iter()
+            .filter(|(type_name, _)| type_name.ends_with(name))
+            .map(|&(_, lock)| lock)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn reports_acquisition_blocked_by_held_write_lock() {
+        static GLOBAL: GlobalMockable<Watched> = GlobalMockable::const_new();
+        set_handler(record);
+        set_threshold(Duration::from_millis(20));
+
+        let write = GLOBAL.lock.write().await;
+        let reader = tokio::spawn(GLOBAL.get_or_init(|| async { Arc::new(Watched) }));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(reports_for("::Watched"), ["write"]);
+
+        drop(write);
+        reader.await.unwrap();
+    }
+
+    #[test]
+    fn works_on_runtime_without_timer() {
+        static GLOBAL: GlobalMockable<Untimed> = GlobalMockable::const_new();
+        set_handler(record);
+        set_threshold(Duration::from_millis(20));
+        let runtime = tokio::runtime::Builder::new_multi_thread().build().unwrap();
+
+        runtime.block_on(async {
+            let write = GLOBAL.lock.write().await;
+            let reader = tokio::spawn(GLOBAL.get_or_init(|| async { Arc::new(Untimed) }));
+            std::thread::sleep(Duration::from_millis(100));
+            assert_eq!(reports_for("::Untimed"), ["write"]);
+
+            drop(write);
+            reader.await.unwrap();
+        });
+    }
+}
@@ -0,0 +1,6 @@
+#![cfg(not(loom))]
+
+#[test]
+fn ui() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
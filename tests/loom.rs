@@ -0,0 +1,109 @@
+//! Model checks of `GlobalMockable` under every interleaving loom can find.
+//!
+//! Run with:
+//!
+//! ```text
+//! RUSTFLAGS="--cfg loom" cargo test --release --test loom
+//! ```
+#![cfg(loom)]
+
+use std::sync::Arc;
+
+use global_mockable::GlobalMockable;
+use loom::future::block_on;
+use loom::sync::atomic::{AtomicUsize, Ordering};
+use loom::thread;
+
+fn shared() -> Arc<GlobalMockable<usize>> {
+    Arc::new(GlobalMockable::const_new())
+}
+
+async fn get(global: &GlobalMockable<usize>, default: usize) -> Arc<usize> {
+    global
+        .get_or_init(|| async move { Arc::new(default) })
+        .await
+}
+
+#[test]
+fn get_after_set_observes_value_until_next_mutation() {
+    loom::model(|| {
+        let global = shared();
+        block_on(global.set(Arc::new(1)));
+
+        let writer = thread::spawn({
+            let global = Arc::clone(&global);
+            move || {
+                block_on(async {
+                    global.set(Arc::new(2)).await;
+                    assert_eq!(*get(&global, 0).await, 2);
+                })
+            }
+        });
+        let observed = block_on(get(&global, 0));
+        assert!(*observed == 1 || *observed == 2);
+
+        writer.join().unwrap();
+        assert_eq!(*block_on(get(&global, 0)), 2);
+    });
+}
+
+#[test]
+fn concurrent_initialization_runs_once() {
+    loom::model(|| {
+        let global = shared();
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let init = |global: Arc<GlobalMockable<usize>>, calls: Arc<AtomicUsize>| {
+            move || {
+                block_on(global.get_or_init(|| async move {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    Arc::new(1)
+                }))
+            }
+        };
+
+        let other = thread::spawn(init(Arc::clone(&global), Arc::clone(&calls)));
+        let mine = init(Arc::clone(&global), Arc::clone(&calls))();
+        let theirs = other.join().unwrap();
+
+        assert!(Arc::ptr_eq(&mine, &theirs));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    });
+}
+
+#[test]
+fn set_always_wins_over_racing_initializer() {
+    loom::model(|| {
+        let global = shared();
+
+        let setter = thread::spawn({
+            let global = Arc::clone(&global);
+            move || block_on(global.set(Arc::new(99)))
+        });
+        block_on(get(&global, 1));
+        setter.join().unwrap();
+
+        assert_eq!(*block_on(get(&global, 1)), 99);
+    });
+}
+
+#[test]
+fn clear_racing_initializer_leaves_consistent_state() {
+    loom::model(|| {
+        let global = shared();
+        block_on(global.set(Arc::new(99)));
+
+        let clearer = thread::spawn({
+            let global = Arc::clone(&global);
+            move || block_on(global.clear())
+        });
+        let observed = block_on(get(&global, 1));
+        assert!(*observed == 99 || *observed == 1);
+        clearer.join().unwrap();
+
+        // Whatever survived the race, the cache and the stored value agree.
+        let current = block_on(get(&global, 2));
+        let stored = block_on(global.downgrade()).and_then(|weak| weak.upgrade());
+        assert!(Arc::ptr_eq(&current, &stored.unwrap()));
+    });
+}
@@ -0,0 +1,62 @@
+#![cfg(all(feature = "macros", not(loom)))]
+
+use std::sync::Arc;
+
+#[global_mockable::mockable]
+pub trait Greeter: Send + Sync {
+    fn greet(&self) -> &'static str;
+
+    #[default_impl]
+    async fn default_greeter() -> Arc<dyn Greeter> {
+        Arc::new(DefaultGreeter)
+    }
+}
+
+struct DefaultGreeter;
+
+impl Greeter for DefaultGreeter {
+    fn greet(&self) -> &'static str {
+        "hello"
+    }
+}
+
+struct MockGreeter;
+
+impl Greeter for MockGreeter {
+    fn greet(&self) -> &'static str {
+        "mock hello"
+    }
+}
+
+async fn default_counter() -> Arc<dyn Counter> {
+    Arc::new(Zero)
+}
+
+#[global_mockable::mockable(name = Counters, default = default_counter)]
+pub trait Counter: Send + Sync {
+    fn count(&self) -> usize;
+}
+
+struct Zero;
+
+impl Counter for Zero {
+    fn count(&self) -> usize {
+        0
+    }
+}
+
+#[tokio::test]
+async fn generated_accessor_uses_default_impl() {
+    assert_eq!(GreeterGlobal::get().await.greet(), "hello");
+
+    GreeterGlobal::set(Arc::new(MockGreeter)).await;
+    assert_eq!(GreeterGlobal::get().await.greet(), "mock hello");
+
+    GreeterGlobal::clear().await;
+    assert_eq!(GreeterGlobal::get().await.greet(), "hello");
+}
+
+#[tokio::test]
+async fn accessor_name_and_default_can_be_given() {
+    assert_eq!(Counters::get().await.count(), 0);
+}
@@ -0,0 +1,92 @@
+#![cfg(not(loom))]
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use global_mockable::{MockableState, Origin, define_global_mockable, init_all, snapshot_all};
+
+// `init_all` and `snapshot_all` see every mockable in the binary, so these
+// tests live apart from the unit tests, which `init_all` would disturb.
+
+pub trait Greeter: Send + Sync {
+    fn greet(&self) -> &'static str;
+}
+
+struct DefaultGreeter;
+
+impl Greeter for DefaultGreeter {
+    fn greet(&self) -> &'static str {
+        "hello"
+    }
+}
+
+struct MockGreeter;
+
+impl Greeter for MockGreeter {
+    fn greet(&self) -> &'static str {
+        "mock hello"
+    }
+}
+
+async fn default_greeter() -> Arc<dyn Greeter> {
+    Arc::new(DefaultGreeter)
+}
+
+static FIRST_INIT_CALLS: AtomicUsize = AtomicUsize::new(0);
+static SECOND_INIT_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+async fn first_eager_greeter() -> Arc<dyn Greeter> {
+    FIRST_INIT_CALLS.fetch_add(1, Ordering::SeqCst);
+    Arc::new(DefaultGreeter)
+}
+
+async fn second_eager_greeter() -> Arc<dyn Greeter> {
+    SECOND_INIT_CALLS.fetch_add(1, Ordering::SeqCst);
+    Arc::new(DefaultGreeter)
+}
+
+define_global_mockable!(FirstEagerGreeter, dyn Greeter, first_eager_greeter);
+define_global_mockable!(SecondEagerGreeter, dyn Greeter, second_eager_greeter);
+
+#[tokio::test]
+async fn init_all_initializes_every_registered_mockable() {
+    init_all().await.unwrap();
+    assert_eq!(FIRST_INIT_CALLS.load(Ordering::SeqCst), 1);
+    assert_eq!(SECOND_INIT_CALLS.load(Ordering::SeqCst), 1);
+
+    init_all().await.unwrap();
+    FirstEagerGreeter::get().await;
+    SecondEagerGreeter::get().await;
+    assert_eq!(FIRST_INIT_CALLS.load(Ordering::SeqCst), 1);
+    assert_eq!(SECOND_INIT_CALLS.load(Ordering::SeqCst), 1);
+}
+
+define_global_mockable!(ExplicitSnapshotGreeter, dyn Greeter, default_greeter);
+define_global_mockable!(DefaultSnapshotGreeter, dyn Greeter, default_greeter);
+
+#[tokio::test]
+async fn snapshot_all_reports_each_mockable() {
+    ExplicitSnapshotGreeter::set(Arc::new(MockGreeter)).await;
+    DefaultSnapshotGreeter::get().await;
+
+    let snapshots = snapshot_all().await;
+    let find = |name: &str| {
+        snapshots
+            .iter()
+            .find(|snapshot| snapshot.name.ends_with(name))
+            .cloned()
+            .unwrap()
+    };
+
+    let explicit = find("::ExplicitSnapshotGreeter");
+    assert_eq!(explicit.state, MockableState::Initialized(Origin::Explicit));
+    assert_eq!(
+        explicit.generation,
+        ExplicitSnapshotGreeter::static_instance()
+            .generation()
+            .await
+    );
+    let default = find("::DefaultSnapshotGreeter");
+    assert_eq!(default.state, MockableState::Initialized(Origin::Default));
+    assert_eq!(default.generation, 1);
+}
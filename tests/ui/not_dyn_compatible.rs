@@ -0,0 +1,7 @@
+pub trait Cloner: Send + Sync {
+    fn convert<T>(&self, value: T) -> T;
+}
+
+global_mockable::define_global_mockable!(ClonerGlobal, dyn Cloner, std::future::pending);
+
+fn main() {}
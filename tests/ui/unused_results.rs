@@ -0,0 +1,16 @@
+#![deny(unused_must_use)]
+
+use std::sync::Arc;
+
+use global_mockable::GlobalMockable;
+
+static GLOBAL: GlobalMockable<u8> = GlobalMockable::const_new();
+
+async fn forgets_results() {
+    GLOBAL.swap(Arc::new(1)).await;
+    GLOBAL.clone_inner().await;
+}
+
+fn main() {
+    drop(forgets_results());
+}